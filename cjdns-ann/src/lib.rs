@@ -79,7 +79,7 @@
 //! # Entities
 //!
 //! Every entity in announcement message begins with two bytes, indicating length and type, at the time of this writing the types of entities are:
-//! 1. `EncodingScheme` with type number `0`. The entity contains serialized representation of encoding scheme created by [serializer](../fn.serialize_forms.html). Please look [here](../fn.deserialize_forms.html) for more information about how this is parsed.
+//! 1. `EncodingScheme` with type number `0`. The entity contains serialized representation of encoding scheme created by [serializer](../cjdns_core/fn.serialize_scheme.html). Please look [here](../cjdns_core/fn.deserialize_scheme.html) for more information about how this is parsed.
 //! 2. `Peer` with type number `1`. Each `Peer` entity contains roughly the information which is needed to reach the announcer from a given peer. It is important to note that this is *not* about ability to reach the *peer*, but to reach the announcer if one can already reach said peer.
 //! 3. `NodeProtocolVersion` with type number `2`. The entity tells the protocol version of the node sending it.
 //! 4. `LinkState` with type number `3`.
@@ -0,0 +1,28 @@
+//! Encoding scheme serialization benchmarks.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use cjdns_core::{deserialize_scheme, schemes, serialize_scheme, EncodingScheme, EncodingSchemeForm};
+
+/// Scheme with max amount of forms: `bit_count` is 1..=31, all prefixes are 5 bits long.
+fn big_scheme() -> EncodingScheme {
+    let forms = (0..31)
+        .map(|i| EncodingSchemeForm::try_new(i + 1, 5, i as u32).expect("invalid form"))
+        .collect::<Vec<_>>();
+    EncodingScheme::try_new(&forms).expect("invalid scheme")
+}
+
+fn bench_scheme(c: &mut Criterion, name: &str, scheme: &EncodingScheme) {
+    let serialized = serialize_scheme(scheme).expect("failed to serialize");
+
+    c.bench_function(&format!("serialize {}", name), |b| b.iter(|| serialize_scheme(black_box(scheme))));
+    c.bench_function(&format!("deserialize {}", name), |b| b.iter(|| deserialize_scheme(black_box(&serialized))));
+}
+
+fn encoding_benchmark(c: &mut Criterion) {
+    bench_scheme(c, "v358", &schemes::V358);
+    bench_scheme(c, "31 forms", &big_scheme());
+}
+
+criterion_group!(benches, encoding_benchmark);
+criterion_main!(benches);
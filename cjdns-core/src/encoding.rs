@@ -33,29 +33,58 @@
 //! ```
 
 pub use encoding_scheme::*;
-pub use encoding_serialization::{deserialize_scheme, serialize_scheme};
-pub use errors::{EncodingSerializationError, SchemeValidationError};
+pub use encoding_serialization::{
+    deserialize_forms_into, deserialize_scheme, deserialize_scheme_prefixed, deserialize_scheme_with, deserialize_schemes, serialize_scheme,
+    serialize_scheme_into,
+};
+#[cfg(feature = "std")]
+pub use encoding_serialization::{deserialize_scheme_from, deserialize_scheme_hex, serialize_scheme_hex, serialize_scheme_to};
+#[cfg(feature = "serde_json")]
+pub use errors::AdminJsonSchemeError;
+pub use errors::{EncodingSerializationError, FormValidationError, SchemeParseError, SchemeValidationError};
 
 mod encoding_serialization {
     //! Serialization and deserialization logic
 
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    #[cfg(feature = "std")]
+    use std::io::{self, Read, Write};
+
     use super::EncodingSerializationError;
-    use crate::{EncodingScheme, EncodingSchemeForm};
+    use crate::{EncodingScheme, EncodingSchemeForm, ValidationMode};
 
     /// Store encoding scheme into a byte vector array (bits sequence).
     ///
     /// Accepts `EncodingScheme`, encodes it as bits sequence
     /// and returns the result as bytes vector.
     pub fn serialize_scheme(scheme: &EncodingScheme) -> Result<Vec<u8>, EncodingSerializationError> {
-        let mut result_vec: Vec<u8> = [].to_vec();
-        let mut pos = 0_u32;
-        let mut cur_byte_num = 0;
-        let mut cur_bit_num = 0_u8;
+        let mut result_vec = vec![0; scheme.serialized_len()];
+        let len = serialize_scheme_into(scheme, &mut result_vec)?;
+        debug_assert_eq!(len, result_vec.len());
+        Ok(result_vec)
+    }
+
+    /// Store encoding scheme into the beginning of `buf`, same as `serialize_scheme` but without allocation,
+    /// and return the amount of bytes written. Bytes of `buf` after them are left untouched.
+    ///
+    /// Serialized scheme takes `scheme.serialized_len()` bytes, which is never more than `EncodingScheme::MAX_SERIALIZED_LEN`,
+    /// so a buffer of that size fits any scheme. Returns `EncodingSerializationError::BufferTooSmall` if `buf` is shorter
+    /// than the serialized scheme.
+    pub fn serialize_scheme_into(scheme: &EncodingScheme, buf: &mut [u8]) -> Result<usize, EncodingSerializationError> {
+        if buf.len() < scheme.serialized_len() {
+            return Err(EncodingSerializationError::BufferTooSmall);
+        }
+        let mut len = 0;
+        // bits not yet flushed into `buf`, the earliest ones are the least significant;
+        // there are always less than 8 of them left between forms, and any form takes at most
+        // 5 + 5 + 31 bits, so the accumulator never overflows
+        let mut acc = 0_u64;
+        let mut acc_bits = 0_u32;
 
         for form in scheme.iter() {
             let (bit_count, prefix_len, prefix) = form.params();
-            // any form can be packed in u64
-            let mut acc = 0_u64;
 
             if prefix_len > 31 {
                 return Err(EncodingSerializationError::BadEncodingForm);
@@ -65,80 +94,214 @@ mod encoding_serialization {
                 return Err(EncodingSerializationError::BadEncodingForm);
             }
 
-            if prefix_len > 0 {
-                acc = acc | prefix as u64;
-            }
-
-            acc = acc << 5;
-            acc = acc | bit_count as u64;
+            // form is packed as `prefix | bit_count (5 bits) | prefix_len (5 bits)`, from MSB to LSB
+            let form_bits = ((prefix as u64) << 10) | ((bit_count as u64) << 5) | prefix_len as u64;
+            acc |= form_bits << acc_bits;
+            acc_bits += 5 + 5 + prefix_len as u32;
 
-            acc = acc << 5;
-            acc = acc | prefix_len as u64;
-
-            let bits_needed = 5 + 5 + prefix_len;
-
-            for _ in 0..bits_needed {
-                if pos % 8 == 0 {
-                    // start to work with new byte on each 8-th bit (alloc new byte in result_vec)
-                    result_vec.push(0);
-                    cur_byte_num = cur_byte_num + 1;
-                    cur_bit_num = 0;
-                }
-                let mask = 1 << cur_bit_num;
-                if (acc % 2) == 1 {
-                    result_vec[cur_byte_num - 1] = result_vec[cur_byte_num - 1] | mask;
-                }
-                acc = acc >> 1;
-                cur_bit_num = cur_bit_num + 1;
-                pos = pos + 1;
+            while acc_bits >= 8 {
+                buf[len] = acc as u8;
+                len += 1;
+                acc >>= 8;
+                acc_bits -= 8;
             }
+        }
 
-            assert_eq!(acc, 0);
+        if acc_bits > 0 {
+            // last partial byte, zero-padded in the most significant bits
+            buf[len] = acc as u8;
+            len += 1;
         }
 
-        Ok(result_vec)
+        Ok(len)
     }
 
     /// Parse byte vector array (bits sequence) and transform it to encoding scheme.
     ///
     /// Parses bytes array into `EncodingScheme`.
+    ///
+    /// The whole `scheme_bytes` slice is taken as the serialized scheme. The serialization is not self-delimiting:
+    /// forms are read starting from the last byte, so trailing data would be parsed as forms, and there is no way
+    /// to tell where a scheme ends within a larger buffer. The length of a scheme embedded in a message
+    /// must come from the message itself, see `deserialize_scheme_from`, `deserialize_scheme_prefixed` and `deserialize_schemes`.
     pub fn deserialize_scheme(scheme_bytes: &[u8]) -> Result<EncodingScheme, EncodingSerializationError> {
-        if scheme_bytes.len() < 2 {
+        deserialize_scheme_with(scheme_bytes, ValidationMode::Strict)
+    }
+
+    /// Parses serialized scheme, same as `deserialize_scheme`, but validates it with the given set of rules,
+    /// see `EncodingScheme::try_new_with`.
+    pub fn deserialize_scheme_with(scheme_bytes: &[u8], mode: ValidationMode) -> Result<EncodingScheme, EncodingSerializationError> {
+        let mut forms = Vec::new();
+        deserialize_forms_into(scheme_bytes, &mut forms)?;
+        let ret_scheme = EncodingScheme::try_new_with(&forms, mode).map_err(|_| EncodingSerializationError::BadSerializedData)?;
+        Ok(ret_scheme)
+    }
+
+    /// Parses serialized scheme forms into `out`, same as `deserialize_scheme`, but reusing `out` allocation.
+    ///
+    /// `out` is cleared first, and is left empty if `scheme_bytes` can't be parsed. Unlike `deserialize_scheme`, this
+    /// doesn't check that the forms make a valid scheme, use `EncodingScheme::validate` or `EncodingScheme::try_new` for that.
+    pub fn deserialize_forms_into(scheme_bytes: &[u8], out: &mut Vec<EncodingSchemeForm>) -> Result<(), EncodingSerializationError> {
+        out.clear();
+        let res = read_forms(scheme_bytes, out);
+        if res.is_err() {
+            out.clear();
+        }
+        res
+    }
+
+    fn read_forms(scheme_bytes: &[u8], result: &mut Vec<EncodingSchemeForm>) -> Result<(), EncodingSerializationError> {
+        if scheme_bytes.is_empty() {
+            return Err(EncodingSerializationError::EmptyInput);
+        }
+        if scheme_bytes.len() < 2 || scheme_bytes.len() > EncodingScheme::MAX_SERIALIZED_LEN {
+            // a single form takes at least 10 bits
             return Err(EncodingSerializationError::BadSerializedData);
         }
 
-        let mut result = Vec::new();
         let mut cur_pos = (scheme_bytes.len() * 8) as u32;
 
         loop {
-            cur_pos = cur_pos - 5;
-            let prefix_len = read_bits(scheme_bytes, cur_pos, 5);
+            cur_pos = cur_pos.checked_sub(5).ok_or(EncodingSerializationError::BadSerializedData)?;
+            let prefix_len = read_bits(scheme_bytes, cur_pos, 5)?;
 
-            cur_pos = cur_pos - 5;
-            let bit_count = read_bits(scheme_bytes, cur_pos, 5);
+            cur_pos = cur_pos.checked_sub(5).ok_or(EncodingSerializationError::BadSerializedData)?;
+            let bit_count = read_bits(scheme_bytes, cur_pos, 5)?;
 
-            cur_pos = cur_pos - prefix_len;
+            // truncated input: not enough bits left for the prefix
+            cur_pos = cur_pos.checked_sub(prefix_len).ok_or(EncodingSerializationError::BadSerializedData)?;
 
             // if prefix_len == 0 we simply read 0 bits from current position, receiving prefix = 0
-            let prefix = read_bits(scheme_bytes, cur_pos, prefix_len as u8);
+            let prefix = read_bits(scheme_bytes, cur_pos, prefix_len as u8)?;
 
-            result.push(EncodingSchemeForm::try_new(bit_count as u8, prefix_len as u8, prefix).expect("invalid encoding scheme form"));
+            // 5 bit fields can't exceed 31, but `bit_count == 0` has to be rejected here, so that no structurally invalid form
+            // is ever produced, even if the scheme validation is skipped, as in `deserialize_forms_into`
+            let form = EncodingSchemeForm::try_new(bit_count as u8, prefix_len as u8, prefix).map_err(|_| EncodingSerializationError::BadSerializedData)?;
+            result.push(form);
             if cur_pos < (5 + 5) {
                 // minimum size of scheme from (prefix_len == 0)
                 break;
             }
         }
-        let ret_scheme = EncodingScheme::try_new(&result).map_err(|_| EncodingSerializationError::BadSerializedData)?;
-        Ok(ret_scheme)
+
+        // remaining bits are padding, which must be zeroed for the encoding to be canonical
+        if read_bits(scheme_bytes, 0, cur_pos as u8)? != 0 {
+            return Err(EncodingSerializationError::BadSerializedData);
+        }
+        Ok(())
+    }
+
+    /// Parse several serialized encoding schemes from one buffer, e.g. a node announcement.
+    ///
+    /// Each scheme is given by its `(offset, len)` in `buf` and is parsed with `deserialize_scheme`.
+    /// Stops on the first failure, returning index of the failed scheme along with the error.
+    /// A scheme which is out of `buf` bounds fails with `EncodingSerializationError::BadSerializedData` error.
+    pub fn deserialize_schemes(buf: &[u8], offsets: &[(usize, usize)]) -> Result<Vec<EncodingScheme>, (usize, EncodingSerializationError)> {
+        offsets
+            .iter()
+            .enumerate()
+            .map(|(i, &(offset, len))| {
+                let scheme_bytes = offset
+                    .checked_add(len)
+                    .and_then(|end| buf.get(offset..end))
+                    .ok_or((i, EncodingSerializationError::BadSerializedData))?;
+                deserialize_scheme(scheme_bytes).map_err(|e| (i, e))
+            })
+            .collect()
     }
 
-    fn read_bits(data: &[u8], position: u32, bits_amount: u8) -> u32 {
-        assert!(bits_amount <= 32); // It is a programming error to request more than 32 bits
-        assert!(position + bits_amount as u32 <= (data.len() as u32) * 8); // Programming error to read bits beyond input buffer
+    /// Parse encoding scheme preceded by a single byte holding its serialized length, as framed in some messages.
+    ///
+    /// Returns the scheme along with the total amount of bytes consumed (the length byte included),
+    /// so that parsing of the rest of `bytes` can go on right after the scheme.
+    /// A length byte is enough for any scheme, as serialized scheme is at most `EncodingScheme::MAX_SERIALIZED_LEN` bytes long.
+    /// Declared length exceeding `bytes` results in `EncodingSerializationError::BadSerializedData` error.
+    pub fn deserialize_scheme_prefixed(bytes: &[u8]) -> Result<(EncodingScheme, usize), EncodingSerializationError> {
+        let (&len, rest) = bytes.split_first().ok_or(EncodingSerializationError::EmptyInput)?;
+        let scheme_bytes = rest.get(..len as usize).ok_or(EncodingSerializationError::BadSerializedData)?;
+        let scheme = deserialize_scheme(scheme_bytes)?;
+        Ok((scheme, 1 + scheme_bytes.len()))
+    }
+
+    /// Store encoding scheme into a lowercase hex string.
+    ///
+    /// The output matches hex representation used by the reference
+    /// [cjdnsencode](https://github.com/cjdelisle/cjdnsencode) implementation, e.g. `"6114458100"`.
+    #[cfg(feature = "std")]
+    pub fn serialize_scheme_hex(scheme: &EncodingScheme) -> Result<String, EncodingSerializationError> {
+        serialize_scheme(scheme).map(hex::encode)
+    }
+
+    /// Parse hex string and transform it to encoding scheme.
+    ///
+    /// Malformed or odd-length hex string results in `EncodingSerializationError::BadSerializedData` error.
+    #[cfg(feature = "std")]
+    pub fn deserialize_scheme_hex(scheme_hex: &str) -> Result<EncodingScheme, EncodingSerializationError> {
+        let scheme_bytes = hex::decode(scheme_hex).map_err(|_| EncodingSerializationError::BadSerializedData)?;
+        deserialize_scheme(&scheme_bytes)
+    }
+
+    /// Store encoding scheme into a writer, e.g. a network stream.
+    ///
+    /// Serialization errors are returned as `io::ErrorKind::InvalidInput` errors.
+    #[cfg(feature = "std")]
+    pub fn serialize_scheme_to<W: Write>(scheme: &EncodingScheme, writer: &mut W) -> io::Result<()> {
+        let scheme_bytes = serialize_scheme(scheme).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        writer.write_all(&scheme_bytes)
+    }
+
+    /// Read exactly `len` bytes of serialized encoding scheme from a reader and parse them.
+    ///
+    /// Serialized scheme is not self-delimiting, so its length must be known in advance, usually from a message header.
+    /// Errors of the reader are returned as is, e.g. `io::ErrorKind::UnexpectedEof` if the reader has less than `len` bytes,
+    /// deserialization errors are returned as `io::ErrorKind::InvalidData` errors.
+    #[cfg(feature = "std")]
+    pub fn deserialize_scheme_from<R: Read>(reader: &mut R, len: usize) -> io::Result<EncodingScheme> {
+        if len > EncodingScheme::MAX_SERIALIZED_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, EncodingSerializationError::BadSerializedData));
+        }
+        let mut scheme_bytes = vec![0; len];
+        reader.read_exact(&mut scheme_bytes)?;
+        deserialize_scheme(&scheme_bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Same as `deserialize_scheme`.
+    impl TryFrom<&[u8]> for EncodingScheme {
+        type Error = EncodingSerializationError;
+
+        fn try_from(scheme_bytes: &[u8]) -> Result<Self, Self::Error> {
+            deserialize_scheme(scheme_bytes)
+        }
+    }
+
+    /// Same as `deserialize_scheme`, takes ownership of the bytes, e.g. of a buffer which is no longer needed.
+    impl TryFrom<Vec<u8>> for EncodingScheme {
+        type Error = EncodingSerializationError;
+
+        fn try_from(scheme_bytes: Vec<u8>) -> Result<Self, Self::Error> {
+            deserialize_scheme(&scheme_bytes)
+        }
+    }
+
+    /// Same as `serialize_scheme`, which never fails for a valid scheme.
+    impl From<&EncodingScheme> for Vec<u8> {
+        fn from(scheme: &EncodingScheme) -> Self {
+            scheme.serialize()
+        }
+    }
+
+    /// Reads `bits_amount` bits starting from `position`.
+    ///
+    /// Returns `EncodingSerializationError::BadSerializedData` instead of panicking if requested bits are out of `data` bounds,
+    /// because `data` usually comes from the network.
+    fn read_bits(data: &[u8], position: u32, bits_amount: u8) -> Result<u32, EncodingSerializationError> {
+        if bits_amount > 32 || position as u64 + bits_amount as u64 > data.len() as u64 * 8 {
+            return Err(EncodingSerializationError::BadSerializedData);
+        }
 
         let mut acc = 0; // maximum that can be parsed is prefix itself (max - 32 bits)
         if bits_amount == 0 {
-            return acc; // reading 0 bits from any correct position returns 0x000000
+            return Ok(acc); // reading 0 bits from any correct position returns 0x000000
         }
         let mut pos = position;
         let mut cur_byte_num;
@@ -165,13 +328,19 @@ mod encoding_serialization {
             pos = pos + 1;
             bits_left = bits_left - 1;
         }
-        acc
+        Ok(acc)
     }
 
-    #[cfg(test)]
+    #[cfg(all(test, feature = "std"))]
     mod tests {
+        use std::collections::BTreeSet;
+        use std::convert::TryInto;
+
+        use rand::rngs::SmallRng;
+        use rand::{Rng, SeedableRng};
+
         use super::*;
-        use crate::{EncodingScheme, SchemeValidationError};
+        use crate::{schemes, EncodingScheme, SchemeValidationError};
 
         fn encoding_scheme(forms: &[EncodingSchemeForm]) -> EncodingScheme {
             EncodingScheme::try_new(forms).expect("invalid scheme")
@@ -217,6 +386,22 @@ mod encoding_serialization {
             // test non-unique prefix in multiple forms
             input = [encoding_form(3, 3, 1), encoding_form(4, 4, 2), encoding_form(5, 5, 6), encoding_form(8, 9, 2)].to_vec();
             assert_eq!(validate(&input), Err(SchemeValidationError::DuplicatePrefix));
+
+            // test prefix matching lower bits of another prefix: `11` ends with `1`
+            input = [encoding_form(3, 1, 0b1), encoding_form(5, 2, 0b11), encoding_form(8, 2, 0b00)].to_vec();
+            assert_eq!(validate(&input), Err(SchemeValidationError::AmbiguousPrefixes));
+            input = [encoding_form(3, 2, 0b10), encoding_form(5, 3, 0b110), encoding_form(8, 1, 0b1)].to_vec();
+            assert_eq!(validate(&input), Err(SchemeValidationError::AmbiguousPrefixes));
+            input = [encoding_form(4, 3, 0b010), encoding_form(8, 1, 0b0)].to_vec();
+            assert_eq!(validate(&input), Err(SchemeValidationError::AmbiguousPrefixes));
+            input = [encoding_form(4, 1, 0b1), encoding_form(8, 3, 0b101)].to_vec();
+            assert_eq!(validate(&input), Err(SchemeValidationError::AmbiguousPrefixes));
+
+            // prefixes of different length which are not lower bits of each other are fine
+            input = [encoding_form(3, 1, 0b1), encoding_form(5, 2, 0b10), encoding_form(8, 2, 0b00)].to_vec();
+            assert!(validate(&input).is_ok());
+            input = [encoding_form(4, 1, 0b1), encoding_form(6, 3, 0b010), encoding_form(8, 3, 0b100)].to_vec();
+            assert!(validate(&input).is_ok());
         }
 
         #[test]
@@ -282,6 +467,269 @@ mod encoding_serialization {
             assert!(validate(&deserialized).is_ok());
         }
 
+        #[test]
+        fn test_zero_bytes() {
+            // zero bytes are valid anywhere inside a serialized scheme,
+            // deserialization is driven by the amount of consumed bits, not by byte values
+            let cases = vec![
+                (vec![encoding_form(8, 0, 0)], vec![0x00, 0x01]),
+                (vec![encoding_form(16, 0, 0)], vec![0x00, 0x02]),
+                (
+                    vec![encoding_form(4, 24, 1), encoding_form(8, 24, 0)],
+                    vec![0x98, 0x04, 0x00, 0x00, 0x60, 0x04, 0x00, 0x00, 0x00],
+                ),
+                (
+                    vec![encoding_form(8, 20, 0), encoding_form(16, 20, 1)],
+                    vec![0x14, 0x01, 0x00, 0x00, 0x85, 0x01, 0x00, 0x00],
+                ),
+                (
+                    vec![encoding_form(1, 31, 1 << 30), encoding_form(2, 31, 0)],
+                    vec![0x3f, 0x00, 0x00, 0x00, 0x00, 0xbf, 0x00, 0x00, 0x00, 0x00, 0x00],
+                ),
+            ];
+            for (forms, bytes) in cases {
+                let scheme = encoding_scheme(&forms);
+                assert_eq!(serialize_scheme(&scheme), Ok(bytes.clone()));
+                assert_eq!(deserialize_scheme(&bytes), Ok(scheme));
+
+                // extra zero padding of a whole byte or more is not skipped, it breaks the bit layout of the forms,
+                // so it is rejected and every scheme has exactly one serialized representation
+                for padding_len in 1..4 {
+                    let mut padded = vec![0; padding_len];
+                    padded.extend_from_slice(&bytes);
+                    assert_eq!(deserialize_scheme(&padded), Err(EncodingSerializationError::BadSerializedData));
+                    let mut padded = bytes.clone();
+                    padded.resize(bytes.len() + padding_len, 0);
+                    assert_eq!(deserialize_scheme(&padded), Err(EncodingSerializationError::BadSerializedData));
+                }
+            }
+        }
+
+        #[test]
+        fn test_malformed_data() {
+            assert_eq!(deserialize_scheme(&[]), Err(EncodingSerializationError::EmptyInput));
+            assert_eq!(deserialize_scheme_hex(""), Err(EncodingSerializationError::EmptyInput));
+            assert_eq!(deserialize_scheme(&[0x80]), Err(EncodingSerializationError::BadSerializedData));
+            // prefix_len = 31 doesn't fit in the remaining bits
+            assert_eq!(deserialize_scheme(&[0xff, 0xff]), Err(EncodingSerializationError::BadSerializedData));
+            // bit_count = 0
+            assert_eq!(deserialize_scheme(&[0x00, 0x00]), Err(EncodingSerializationError::BadSerializedData));
+            // bit_count = 0 in the second form of `[0x81, 0x0c, 0x08]`
+            assert_eq!(deserialize_scheme(&[0x81, 0x0c, 0x00]), Err(EncodingSerializationError::BadSerializedData));
+            // non-zero padding bits
+            assert_eq!(deserialize_scheme(&[0x80, 0x40]), Err(EncodingSerializationError::BadSerializedData));
+            assert_eq!(deserialize_scheme(&[0x81, 0x0c, 0x88]), Err(EncodingSerializationError::BadSerializedData));
+            assert_eq!(
+                deserialize_scheme(&[0x61, 0x14, 0x45, 0x81, 0x02]),
+                Err(EncodingSerializationError::BadSerializedData)
+            );
+
+            // every accepted input is canonical, and no input makes deserialization panic:
+            // checked on pairs of edge byte values and on a seeded sample of other inputs
+            const EDGE_BYTES: [u8; 8] = [0x00, 0x01, 0x0f, 0x7f, 0x80, 0x81, 0xfe, 0xff];
+            let edge_pairs = EDGE_BYTES.iter().flat_map(|&a| EDGE_BYTES.iter().map(move |&b| (a, b)));
+            let mut rng = SmallRng::seed_from_u64(0xbad0_da7a_u64);
+            let random_pairs = (0..2_000).map(|_| (rng.gen::<u8>(), rng.gen::<u8>())).collect::<Vec<_>>();
+            for (a, b) in edge_pairs.chain(random_pairs) {
+                let bytes = [a, b];
+                if let Ok(scheme) = deserialize_scheme(&bytes) {
+                    assert_eq!(serialize_scheme(&scheme).expect("failed to serialize"), bytes.to_vec());
+                }
+                let _ = deserialize_scheme(&[a, b, a]);
+            }
+        }
+
+        #[test]
+        fn test_hex() {
+            // https://github.com/cjdelisle/cjdnsencode/blob/89216230daa82eb43689c6af48de3c6a138002f1/test.js
+            let vectors = [
+                ("8000", encoding_scheme(&[encoding_form(4, 0, 0)])),
+                ("0001", encoding_scheme(&[encoding_form(8, 0, 0)])),
+                ("810c08", encoding_scheme(&[encoding_form(4, 1, 1), encoding_form(8, 1, 0)])),
+                (
+                    "6114458100",
+                    encoding_scheme(&[encoding_form(3, 1, 1), encoding_form(5, 2, 2), encoding_form(8, 2, 0)]),
+                ),
+            ];
+            for (hex, scheme) in vectors.iter() {
+                assert_eq!(serialize_scheme_hex(scheme).expect("failed to serialize").as_str(), *hex);
+                assert_eq!(&deserialize_scheme_hex(hex).expect("failed to deserialize"), scheme);
+            }
+
+            assert_eq!(deserialize_scheme_hex("611445810"), Err(EncodingSerializationError::BadSerializedData));
+            assert_eq!(deserialize_scheme_hex("61144581zz"), Err(EncodingSerializationError::BadSerializedData));
+            assert_eq!(deserialize_scheme_hex("0x6114458100"), Err(EncodingSerializationError::BadSerializedData));
+        }
+
+        #[test]
+        fn test_conversions() {
+            let scheme = encoding_scheme(&[encoding_form(3, 1, 1), encoding_form(5, 2, 2), encoding_form(8, 2, 0)]);
+            let serialized = Vec::from(&scheme);
+            assert_eq!(serialized, [0x61, 0x14, 0x45, 0x81, 0x00].to_vec());
+            assert_eq!(serialized, serialize_scheme(&scheme).expect("failed to serialize"));
+            assert_eq!(EncodingScheme::try_from(serialized.as_slice()), Ok(scheme.clone()));
+            assert_eq!(EncodingScheme::try_from(serialized), Ok(scheme));
+
+            let deserialized: Result<EncodingScheme, _> = [0x81_u8, 0x0c, 0x08].as_ref().try_into();
+            assert_eq!(deserialized, Ok(encoding_scheme(&[encoding_form(4, 1, 1), encoding_form(8, 1, 0)])));
+
+            assert_eq!(EncodingScheme::try_from(&[][..]), Err(EncodingSerializationError::EmptyInput));
+            assert_eq!(EncodingScheme::try_from(&[0x00, 0x00][..]), Err(EncodingSerializationError::BadSerializedData));
+
+            // slice and vec conversions agree
+            let cases = vec![
+                vec![],
+                vec![0x00],
+                vec![0x00, 0x00],
+                vec![0x00, 0x01],
+                vec![0x81, 0x0c, 0x08],
+                vec![0x61, 0x14, 0x45, 0x81, 0x00],
+                vec![0xff; 200],
+            ];
+            for bytes in cases {
+                assert_eq!(EncodingScheme::try_from(bytes.as_slice()), EncodingScheme::try_from(bytes.clone()));
+            }
+        }
+
+        #[test]
+        fn test_io() {
+            let scheme = encoding_scheme(&[encoding_form(3, 1, 1), encoding_form(5, 2, 2), encoding_form(8, 2, 0)]);
+            let mut buf = vec![0xff];
+            serialize_scheme_to(&scheme, &mut buf).expect("failed to write");
+            assert_eq!(buf, [0xff, 0x61, 0x14, 0x45, 0x81, 0x00].to_vec());
+
+            // reader is left positioned right after the scheme
+            buf.push(0xee);
+            let mut reader = &buf[1..];
+            assert_eq!(deserialize_scheme_from(&mut reader, 5).expect("failed to read"), scheme);
+            assert_eq!(reader, &[0xee]);
+
+            let err = deserialize_scheme_from(&mut &buf[1..], 7).expect_err("read past the end");
+            assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+            let err = deserialize_scheme_from(&mut &buf[1..], 6).expect_err("trailing byte accepted");
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            let err = deserialize_scheme_from(&mut &buf[1..], 0).expect_err("empty scheme accepted");
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            let err = deserialize_scheme_from(&mut std::io::repeat(0), EncodingScheme::MAX_SERIALIZED_LEN + 1).expect_err("too long scheme accepted");
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+
+        #[test]
+        fn test_batch() {
+            let buf = [0xff, 0x81, 0x0c, 0x08, 0x61, 0x14, 0x45, 0x81, 0x00, 0x00, 0x01];
+            let v48 = encoding_scheme(&[encoding_form(4, 1, 1), encoding_form(8, 1, 0)]);
+            let v358 = encoding_scheme(&[encoding_form(3, 1, 1), encoding_form(5, 2, 2), encoding_form(8, 2, 0)]);
+            let f8 = encoding_scheme(&[encoding_form(8, 0, 0)]);
+
+            assert_eq!(deserialize_schemes(&buf, &[(1, 3), (4, 5), (9, 2)]), Ok(vec![v48.clone(), v358, f8]));
+            assert_eq!(deserialize_schemes(&buf, &[(1, 3), (1, 3)]), Ok(vec![v48.clone(), v48]));
+            assert_eq!(deserialize_schemes(&buf, &[]), Ok(vec![]));
+
+            assert_eq!(
+                deserialize_schemes(&buf, &[(1, 3), (0, 3)]),
+                Err((1, EncodingSerializationError::BadSerializedData))
+            );
+            assert_eq!(deserialize_schemes(&buf, &[(4, 0), (1, 3)]), Err((0, EncodingSerializationError::EmptyInput)));
+            assert_eq!(
+                deserialize_schemes(&buf, &[(1, 3), (9, 3)]),
+                Err((1, EncodingSerializationError::BadSerializedData))
+            );
+            assert_eq!(
+                deserialize_schemes(&buf, &[(usize::MAX, 2)]),
+                Err((0, EncodingSerializationError::BadSerializedData))
+            );
+        }
+
+        #[test]
+        fn test_prefixed() {
+            let v48 = encoding_scheme(&[encoding_form(4, 1, 1), encoding_form(8, 1, 0)]);
+            let f8 = encoding_scheme(&[encoding_form(8, 0, 0)]);
+
+            assert_eq!(deserialize_scheme_prefixed(&[0x03, 0x81, 0x0c, 0x08]), Ok((v48.clone(), 4)));
+            assert_eq!(deserialize_scheme_prefixed(&[0x03, 0x81, 0x0c, 0x08, 0x02, 0x00, 0x01]), Ok((v48, 4)));
+            assert_eq!(deserialize_scheme_prefixed(&[0x02, 0x00, 0x01]), Ok((f8, 3)));
+
+            assert_eq!(deserialize_scheme_prefixed(&[]), Err(EncodingSerializationError::EmptyInput));
+            assert_eq!(deserialize_scheme_prefixed(&[0x00, 0x00, 0x01]), Err(EncodingSerializationError::EmptyInput));
+            assert_eq!(
+                deserialize_scheme_prefixed(&[0x03, 0x81, 0x0c]),
+                Err(EncodingSerializationError::BadSerializedData)
+            );
+            assert_eq!(deserialize_scheme_prefixed(&[0xff; 100]), Err(EncodingSerializationError::BadSerializedData));
+            assert_eq!(
+                deserialize_scheme_prefixed(&[0x02, 0x81, 0x0c, 0x08]),
+                Err(EncodingSerializationError::BadSerializedData)
+            );
+        }
+
+        #[test]
+        fn test_serialize_into() {
+            let mut buf = [0xaa_u8; EncodingScheme::MAX_SERIALIZED_LEN];
+            for scheme in schemes::all() {
+                let serialized = serialize_scheme(scheme).expect("failed to serialize scheme");
+                assert_eq!(serialize_scheme_into(scheme, &mut buf), Ok(serialized.len()));
+                assert_eq!(&buf[..serialized.len()], serialized.as_slice());
+
+                let mut exact = vec![0; serialized.len()];
+                assert_eq!(serialize_scheme_into(scheme, &mut exact), Ok(serialized.len()));
+                assert_eq!(exact, serialized);
+
+                let mut short = vec![0; serialized.len() - 1];
+                assert_eq!(serialize_scheme_into(scheme, &mut short), Err(EncodingSerializationError::BufferTooSmall));
+            }
+
+            // the rest of the buffer is left untouched
+            let mut buf = [0xaa_u8; 4];
+            assert_eq!(serialize_scheme_into(&schemes::V48, &mut buf), Ok(3));
+            assert_eq!(buf, [0x81, 0x0c, 0x08, 0xaa]);
+            assert_eq!(serialize_scheme_into(&schemes::V48, &mut []), Err(EncodingSerializationError::BufferTooSmall));
+        }
+
+        #[test]
+        fn test_forms_into() {
+            let mut forms = Vec::new();
+            for scheme in schemes::all() {
+                let serialized = serialize_scheme(scheme).expect("failed to serialize scheme");
+                assert_eq!(deserialize_forms_into(&serialized, &mut forms), Ok(()));
+                assert_eq!(forms.as_slice(), scheme.forms());
+            }
+
+            // `out` is cleared, not appended to
+            let capacity = forms.capacity();
+            assert_eq!(deserialize_forms_into(&[0x00, 0x01], &mut forms), Ok(()));
+            assert_eq!(forms, [encoding_form(8, 0, 0)]);
+            assert_eq!(forms.capacity(), capacity);
+
+            // forms are not validated as a scheme: single form with a prefix
+            assert_eq!(deserialize_forms_into(&[0x81, 0x04], &mut forms), Ok(()));
+            assert_eq!(forms, [encoding_form(4, 1, 1)]);
+            assert_eq!(deserialize_scheme(&[0x81, 0x04]), Err(EncodingSerializationError::BadSerializedData));
+
+            assert_eq!(
+                deserialize_forms_into(&[0x81, 0x0c, 0x88], &mut forms),
+                Err(EncodingSerializationError::BadSerializedData)
+            );
+            assert!(forms.is_empty());
+            assert_eq!(deserialize_forms_into(&[0x81, 0x0c, 0x08], &mut forms), Ok(()));
+            assert_eq!(deserialize_forms_into(&[], &mut forms), Err(EncodingSerializationError::EmptyInput));
+            assert!(forms.is_empty());
+        }
+
+        #[test]
+        fn test_max_serialized_len() {
+            assert_eq!(EncodingScheme::MAX_FORMS, 31);
+            assert_eq!(EncodingScheme::MAX_SERIALIZED_LEN, 159);
+
+            let forms = (0..31).map(|i| encoding_form(1, 31, 0x7fff_ffff - i)).collect::<Vec<_>>();
+            let serialized = serialize_scheme(&encoding_scheme(&forms)).expect("failed to serialize");
+            assert_eq!(serialized.len(), EncodingScheme::MAX_SERIALIZED_LEN);
+            assert_eq!(deserialize_scheme(&serialized), Ok(encoding_scheme(&forms)));
+
+            let mut too_long = serialized.clone();
+            too_long.push(0);
+            assert_eq!(deserialize_scheme(&too_long), Err(EncodingSerializationError::BadSerializedData));
+        }
+
         #[test]
         fn test_forms_pack_with_sequential_parameters() {
             // test of forms pack with different parameters
@@ -301,16 +749,179 @@ mod encoding_serialization {
             let deserialized = deserialize_scheme(&serialized).expect("failed to deserialize");
             assert_eq!(deserialized, scheme);
         }
+
+        #[test]
+        fn test_random_roundtrip() {
+            let mut rng = SmallRng::seed_from_u64(0x5eed_f0e5_c0de_u64);
+
+            for _ in 0..1000 {
+                let forms_count = rng.gen_range(1, 32);
+                let forms = random_forms(&mut rng, forms_count);
+                assert!(roundtrip_forms(&forms), "round-trip failed for {:?}", forms);
+            }
+
+            // single form schemes
+            for bit_count in 1..32 {
+                assert!(roundtrip_forms(&[encoding_form(bit_count, 0, 0)]));
+            }
+
+            // schemes with max amount of forms
+            for _ in 0..100 {
+                let forms = random_forms(&mut rng, 31);
+                assert!(roundtrip_forms(&forms), "round-trip failed for {:?}", forms);
+            }
+
+            // forms with `bit_count + prefix_len == 59`
+            assert!(roundtrip_forms(&[
+                encoding_form(28, 31, 0x7fff_ffff),
+                encoding_form(29, 30, 0),
+                encoding_form(30, 29, 1),
+                encoding_form(31, 28, 2),
+            ]));
+            for prefix_len in 28..32 {
+                let forms = [encoding_form(1, prefix_len, 0), encoding_form(59 - prefix_len, prefix_len, 1)];
+                assert!(roundtrip_forms(&forms), "round-trip failed for {:?}", forms);
+            }
+        }
+
+        #[test]
+        fn test_validate_implies_serializable() {
+            let mut rng = SmallRng::seed_from_u64(0xa11_f0e5_u64);
+            let mut valid_count = 0;
+
+            for _ in 0..1_000 {
+                // arbitrary forms, mostly with short prefixes and few forms, so that some of them make a valid scheme
+                let forms_count = rng.gen_range(1, 6_usize);
+                let mut forms = (0..forms_count)
+                    .map(|_| {
+                        let prefix_len = if rng.gen_bool(0.9) { rng.gen_range(0, 4) } else { rng.gen_range(0, 32) };
+                        let prefix = rng.gen_range(0, 1_u64 << prefix_len) as u32;
+                        encoding_form(rng.gen_range(1, 32), prefix_len, prefix)
+                    })
+                    .collect::<Vec<_>>();
+                if rng.gen_bool(0.8) {
+                    forms.sort();
+                }
+
+                // any form passing validation must be serializable, and the other way around
+                let validated = validate(&forms);
+                assert_eq!(validated.is_ok(), roundtrip_forms(&forms), "{:?} for {:?}", validated, forms);
+                if validated.is_ok() {
+                    valid_count += 1;
+                }
+            }
+            assert!(valid_count > 50, "too few valid schemes generated: {}", valid_count);
+        }
+
+        /// Checks that `forms` make up a valid scheme which survives the serialization round-trip unchanged.
+        ///
+        /// Returns `false` if the forms are rejected by validation or by the serialization
+        /// or if the deserialized scheme differs from the original one.
+        fn roundtrip_forms(forms: &[EncodingSchemeForm]) -> bool {
+            EncodingScheme::try_new(forms)
+                .ok()
+                .and_then(|scheme| serialize_scheme(&scheme).ok())
+                .and_then(|bytes| deserialize_scheme(&bytes).ok())
+                .map_or(false, |scheme| &*scheme == forms)
+        }
+
+        /// Generates random forms of a valid scheme following the same rules as `EncodingScheme::validate`:
+        /// a single form has no prefix, several forms have non-empty unique prefixes (all of the same length here),
+        /// are sorted by `bit_count` and each fits into 59 bits.
+        fn random_forms(rng: &mut SmallRng, forms_count: usize) -> Vec<EncodingSchemeForm> {
+            assert!(forms_count >= 1 && forms_count <= 31);
+            if forms_count == 1 {
+                return vec![encoding_form(rng.gen_range(1, 32), 0, 0)];
+            }
+
+            // prefixes must be long enough to hold `forms_count` unique values
+            let mut min_prefix_len = 1;
+            while (1_usize << min_prefix_len) < forms_count {
+                min_prefix_len += 1;
+            }
+            let prefix_len = rng.gen_range(min_prefix_len, 32_u8);
+
+            let mut prefixes = BTreeSet::new();
+            while prefixes.len() < forms_count {
+                prefixes.insert(rng.gen_range(0, 1_u64 << prefix_len) as u32);
+            }
+
+            let max_bit_count = std::cmp::min(31, 59 - prefix_len);
+            let mut bit_counts = (0..forms_count).map(|_| rng.gen_range(1, max_bit_count + 1)).collect::<Vec<u8>>();
+            bit_counts.sort();
+
+            bit_counts
+                .into_iter()
+                .zip(prefixes)
+                .map(|(bit_count, prefix)| encoding_form(bit_count, prefix_len, prefix))
+                .collect()
+        }
+    }
+
+    /// Tests which don't need `std` feature, so they run with `--no-default-features` as well.
+    #[cfg(test)]
+    mod alloc_tests {
+        use alloc::vec;
+        use alloc::vec::Vec;
+
+        use super::*;
+        use crate::{EncodingScheme, SchemeValidationError};
+
+        fn encoding_form(bit_count: u8, prefix_len: u8, prefix: u32) -> EncodingSchemeForm {
+            EncodingSchemeForm::try_new(bit_count, prefix_len, prefix).expect("invalid form")
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let v48 = EncodingScheme::try_new(&[encoding_form(4, 1, 1), encoding_form(8, 1, 0)]).expect("invalid scheme");
+            let serialized = serialize_scheme(&v48).expect("serialization failed");
+            assert_eq!(serialized, vec![0x81, 0x0c, 0x08]);
+            assert_eq!(deserialize_scheme(&serialized), Ok(v48.clone()));
+
+            let mut buf = [0; EncodingScheme::MAX_SERIALIZED_LEN];
+            let len = serialize_scheme_into(&v48, &mut buf).expect("serialization failed");
+            assert_eq!(&buf[..len], &serialized[..]);
+            let mut forms = Vec::new();
+            assert_eq!(deserialize_forms_into(&buf[..len], &mut forms), Ok(()));
+            assert_eq!(&forms[..], &v48[..]);
+
+            let f8 = EncodingScheme::try_new(&[encoding_form(8, 0, 0)]).expect("invalid scheme");
+            assert_eq!(deserialize_scheme(&f8.serialize()), Ok(f8));
+        }
+
+        #[test]
+        fn test_invalid_data() {
+            assert_eq!(deserialize_scheme(&[]), Err(EncodingSerializationError::EmptyInput));
+            assert_eq!(deserialize_scheme(&[0x81, 0x0c]), Err(EncodingSerializationError::BadSerializedData));
+            assert_eq!(
+                EncodingScheme::try_new(&[encoding_form(8, 1, 0), encoding_form(4, 1, 1)]),
+                Err(SchemeValidationError::BitCountNotSorted)
+            );
+        }
     }
 }
 
 mod encoding_scheme {
     //! Routing label encoding scheme.
 
-    use std::collections::HashSet;
-    use std::ops::Deref;
-
+    use alloc::format;
+    use alloc::string::String;
+    #[cfg(feature = "arbitrary")]
+    use alloc::vec;
+    use alloc::vec::Vec;
+    #[cfg(feature = "serde_json")]
+    use core::convert::TryFrom;
+    use core::ops::Deref;
+    #[cfg(feature = "std")]
+    use core::str::FromStr;
+
+    #[cfg(feature = "serde_json")]
+    use crate::encoding::errors::AdminJsonSchemeError;
+    #[cfg(feature = "std")]
+    use crate::encoding::errors::SchemeParseError;
     use crate::encoding::errors::{FormValidationError, SchemeValidationError};
+    #[cfg(feature = "std")]
+    use crate::DefaultRoutingLabel;
 
     /// In the old days every label needed to be topped with 0001.
     /// To make sure that no label would ever go over 64 bits even with 0001 spliced on the top of it, we use this reservation.
@@ -321,8 +932,30 @@ mod encoding_scheme {
     /// Encoding scheme - an iterable list of scheme forms.
     ///
     /// Schemes are comparable for equality, immutable, opaque and iterable.
+    ///
+    /// Forms of a scheme created in strict mode (see `ValidationMode`) are always sorted by `bit_count`,
+    /// but forms with equal `bit_count` may go in any order, and schemes differing only in such order are not equal.
+    /// Use `canonical()` or `canonicalize()` to compare them regardless of it.
+    ///
+    /// A scheme created in lenient mode which doesn't pass strict validation, e.g. has unsorted forms or a single form
+    /// with a prefix, keeps track of that, see `validation_mode()`. Such scheme decodes and encodes labels and serializes
+    /// like any other, but the invariants of strict mode above don't hold for it.
+    ///
+    /// Scheme dereferences to a slice of its forms, so it can be indexed and iterated like one:
+    ///
+    /// ```rust
+    /// # use cjdns_core::{EncodingScheme, EncodingSchemeForm};
+    /// # let encoding_form = |bit_count, prefix_len, prefix| EncodingSchemeForm::try_new(bit_count, prefix_len, prefix).expect("invalid encoding form");
+    /// let scheme = EncodingScheme::try_new(&[encoding_form(3, 1, 1), encoding_form(5, 2, 2), encoding_form(8, 2, 0)]).expect("invalid scheme");
+    /// for form in scheme.iter() {
+    ///     println!("{:?} takes {} bits", form, form.size_bits());
+    /// }
+    /// assert_eq!(scheme.len(), 3);
+    /// assert_eq!(scheme[0].size_bits(), 4);
+    /// assert_eq!(scheme.iter().map(|form| form.size_bits()).collect::<Vec<_>>(), vec![4, 7, 10]);
+    /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct EncodingScheme(Vec<EncodingSchemeForm>);
+    pub struct EncodingScheme(Vec<EncodingSchemeForm>, ValidationMode);
 
     /// A form of an encoding scheme. Form is used as follows to encode a director:
     ///
@@ -331,7 +964,15 @@ mod encoding_scheme {
     /// ^^^^^^^^^^^^^^^^^^^^ ^^^^^^^^^^^^^^^^^^^^^^^
     /// form.bit_count bits   form.prefix_len bits
     /// ```
+    ///
+    /// With `serde` feature enabled forms are (de)serialized as `{"bitCount": 4, "prefixLen": 1, "prefix": 1}`,
+    /// same as in cjdns admin API. Deserialized forms are validated as in `EncodingSchemeForm::try_new`.
+    ///
+    /// Forms are ordered by `bit_count`, then `prefix_len`, then `prefix`, which is the order `EncodingScheme::validate` expects,
+    /// so sorting a list of forms is enough to put them into a valid order.
     #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
     pub struct EncodingSchemeForm {
         bit_count: u8, // bit_count going first is important for EncodingScheme ordering
         prefix_len: u8,
@@ -343,11 +984,18 @@ mod encoding_scheme {
         ///
         /// Returns an error in several situations:
         /// * encoding `bit_count` value is out of valid range, which is 1..32
+        /// * `prefix_len` value is out of valid range, which is 0..32
         /// * `prefix_len` is too small for the provided `prefix`
-        pub fn try_new(bit_count: u8, prefix_len: u8, prefix: u32) -> Result<Self, FormValidationError> {
+        ///
+        /// Any form created this way can be serialized.
+        /// This is a `const fn`, so forms can be created in constant expressions too.
+        pub const fn try_new(bit_count: u8, prefix_len: u8, prefix: u32) -> Result<Self, FormValidationError> {
             if bit_count == 0 || bit_count > 31 {
                 return Err(FormValidationError::BadBitCount);
             }
+            if prefix_len > 31 {
+                return Err(FormValidationError::BadPrefixLen);
+            }
             let prefix_max_value = (1 << prefix_len) - 1;
             if prefix > prefix_max_value {
                 return Err(FormValidationError::InvalidPrefixData);
@@ -359,67 +1007,431 @@ mod encoding_scheme {
         /// * bit count;
         /// * prefix length;
         /// * prefix itself.
-        pub fn params(&self) -> (u8, u8, u32) {
+        pub const fn params(&self) -> (u8, u8, u32) {
             (self.bit_count, self.prefix_len, self.prefix)
         }
 
+        /// Sets the form prefix, checking it as `try_new` does.
+        ///
+        /// Returns `FormValidationError::InvalidPrefixData` and leaves the form unchanged if `prefix` doesn't fit in `prefix_len` bits.
+        pub fn set_prefix(&mut self, prefix: u32) -> Result<(), FormValidationError> {
+            *self = Self::try_new(self.bit_count, self.prefix_len, prefix)?;
+            Ok(())
+        }
+
+        /// Sets the form prefix length, checking it and the current prefix as `try_new` does.
+        ///
+        /// Returns `FormValidationError::BadPrefixLen` if `prefix_len` is out of range and `FormValidationError::InvalidPrefixData`
+        /// if the current prefix doesn't fit in `prefix_len` bits, the form is left unchanged in both cases.
+        pub fn set_prefix_len(&mut self, prefix_len: u8) -> Result<(), FormValidationError> {
+            *self = Self::try_new(self.bit_count, prefix_len, self.prefix)?;
+            Ok(())
+        }
+
         /// As a scheme is represented as an array of **forms**, this function will tell you how many bits of
         /// label space is occupied by a representation of a given form.
-        pub fn size_bits(&self) -> u8 {
-            self.bit_count + self.prefix_len
+        ///
+        /// Same as `form_size`, which takes the form by value.
+        pub const fn size_bits(&self) -> u8 {
+            form_size(*self)
+        }
+
+        /// Number of directors of this form which can lead to a peer, i.e. `2^bit_count - 1`.
+        ///
+        /// cjdns reserves interface number `1` for the node itself (it is what the self-route `0000.0000.0000.0001` leads to),
+        /// so one director of every form never addresses a peer. In the 3-bit form of `V358` scheme that is the all-zero
+        /// director, as the form holds `director + 1` (see `splice::label_to_directors`).
+        pub fn usable_directors(&self) -> u64 {
+            (1 << self.bit_count) - 1
+        }
+
+        /// Encodes a director with this form, i.e. places it above the form's prefix: `(director << prefix_len) | prefix`.
+        ///
+        /// Returns `FormValidationError::DirectorTooBig` if director doesn't fit in `bit_count` bits.
+        pub fn encode_director(&self, director: u64) -> Result<u64, FormValidationError> {
+            if director >> self.bit_count != 0 {
+                return Err(FormValidationError::DirectorTooBig);
+            }
+            Ok((director << self.prefix_len) | self.prefix as u64)
+        }
+
+        /// Decodes a director encoded with this form, given the label (or its least significant bits) containing it.
+        ///
+        /// Strips `prefix_len` bits of prefix and returns following `bit_count` bits, the prefix itself is not checked.
+        /// This is the opposite of `encode_director`.
+        pub fn decode_director(&self, bits: u64) -> u64 {
+            let director_mask = (1_u64 << self.bit_count) - 1;
+            (bits >> self.prefix_len) & director_mask
+        }
+    }
+
+    /// Number of bits of label space occupied by a representation of `form`, i.e. `bit_count + prefix_len`.
+    ///
+    /// Unlike `EncodingSchemeForm::size_bits` this takes the form by value, which makes it usable in constant expressions:
+    ///
+    /// ```rust
+    /// # use cjdns_core::{form_size, EncodingSchemeForm};
+    /// const FORM: EncodingSchemeForm = match EncodingSchemeForm::try_new(8, 2, 0) {
+    ///     Ok(form) => form,
+    ///     Err(_) => panic!("invalid scheme form"),
+    /// };
+    /// const FORM_SIZE: u8 = form_size(FORM);
+    /// assert_eq!(FORM_SIZE, 10);
+    /// ```
+    pub const fn form_size(form: EncodingSchemeForm) -> u8 {
+        form.bit_count + form.prefix_len
+    }
+
+    /// Unvalidated form data, used to deserialize `EncodingSchemeForm` through `EncodingSchemeForm::try_new`.
+    #[cfg(feature = "serde")]
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct EncodingSchemeFormData {
+        bit_count: u8,
+        prefix_len: u8,
+        prefix: u32,
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for EncodingSchemeForm {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let data = <EncodingSchemeFormData as serde::Deserialize>::deserialize(deserializer)?;
+            EncodingSchemeForm::try_new(data.bit_count, data.prefix_len, data.prefix).map_err(serde::de::Error::custom)
+        }
+    }
+
+    /// Generates forms passing `EncodingSchemeForm::try_new` checks.
+    #[cfg(feature = "arbitrary")]
+    impl<'a> arbitrary::Arbitrary<'a> for EncodingSchemeForm {
+        fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+            let bit_count = u.int_in_range(1..=31)?;
+            let prefix_len = u.int_in_range(0..=31)?;
+            let prefix = u.arbitrary::<u32>()? & ((1 << prefix_len) - 1);
+            Ok(EncodingSchemeForm { bit_count, prefix_len, prefix })
+        }
+    }
+
+    /// Generates schemes passing `EncodingScheme::validate` checks, so fuzzers don't waste time on trivially rejected input.
+    ///
+    /// Forms of a generated multi-form scheme all have prefixes of the same length, which makes them unambiguous.
+    #[cfg(feature = "arbitrary")]
+    impl<'a> arbitrary::Arbitrary<'a> for EncodingScheme {
+        fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+            let forms_count = u.int_in_range(1..=Self::MAX_FORMS as u32)?;
+            if forms_count == 1 {
+                let bit_count = u.int_in_range(1..=31)?;
+                return Ok(EncodingScheme(
+                    vec![EncodingSchemeForm {
+                        bit_count,
+                        prefix_len: 0,
+                        prefix: 0,
+                    }],
+                    ValidationMode::Strict,
+                ));
+            }
+
+            // prefixes must be long enough to hold `forms_count` unique values, while forms must fit into `FORM_MAX_BIT_SIZE` bits
+            let min_prefix_len = 32 - (forms_count - 1).leading_zeros() as u8;
+            let prefix_len = u.int_in_range(min_prefix_len..=31)?;
+            let max_bit_count = core::cmp::min(31, FORM_MAX_BIT_SIZE - prefix_len);
+            // xor with a fixed mask keeps the prefixes unique
+            let prefix_mask = u.arbitrary::<u32>()? & ((1 << prefix_len) - 1);
+
+            let mut bit_counts = (0..forms_count)
+                .map(|_| u.int_in_range(1..=max_bit_count))
+                .collect::<arbitrary::Result<Vec<u8>>>()?;
+            bit_counts.sort();
+            let forms = bit_counts
+                .into_iter()
+                .zip(0..)
+                .map(|(bit_count, i)| EncodingSchemeForm {
+                    bit_count,
+                    prefix_len,
+                    prefix: i ^ prefix_mask,
+                })
+                .collect::<Vec<_>>();
+            Self::try_new(&forms).map_err(|_| arbitrary::Error::IncorrectFormat)
         }
     }
 
     impl EncodingScheme {
+        /// Max amount of forms in a scheme. Form `bit_count` can only be expressed in 5 bits
+        /// and a form using zero bits is not allowed, so there are only 31 possibilities.
+        pub const MAX_FORMS: usize = 31;
+
+        /// Max length in bytes of a serialized scheme: `MAX_FORMS` forms, each taking 5 + 5 bits
+        /// for `bit_count` and `prefix_len` plus up to 31 bits of prefix.
+        pub const MAX_SERIALIZED_LEN: usize = (Self::MAX_FORMS * (5 + 5 + 31) + 7) / 8;
+
         /// Instantiates `EncodingScheme`.
         ///
         /// Returns an error if forms validation failed. See `validate` function docs for more info.
         pub fn try_new(forms: &[EncodingSchemeForm]) -> Result<Self, SchemeValidationError> {
-            let _ = Self::validate(forms)?;
-            Ok(Self(forms.to_vec()))
+            Self::try_new_with(forms, ValidationMode::Strict)
         }
 
-        /// Validates encoding scheme.
-        ///
-        /// Returns an error in several situations:
-        /// * provided forms slice length is 0 or greater than 31
-        /// * `bit_count` value of any form is out of valid range - 1..32
-        /// * `prefix_len` value of any form is out if valid range - 1..32 (for multiple forms scheme)
-        /// * forms are not in ascending order by `bits_count` key
-        /// * bits size of a form is greater than 59 (for multiple forms scheme)
-        /// * forms with equal prefixes are in scheme
+        /// Instantiates `EncodingScheme` validated with the given set of rules, see `validate_with` and `ValidationMode`.
         ///
-        /// Each returned value fully reflects error type.
-        pub fn validate(forms: &[EncodingSchemeForm]) -> Result<(), SchemeValidationError> {
-            // each form must have a different prefix_len and bit_count;
-            // can only be expressed in 5 bits limiting it to 31 bits max and a form
-            // using zero bits is not allowed so there are only 31 max possibilities.
-            if forms.len() == 0 || forms.len() > 31 {
-                return Err(SchemeValidationError::InvalidFormsAmount);
+        /// Forms which pass lenient validation, but not the strict one, make a scheme with `ValidationMode::Lenient`
+        /// `validation_mode()`, otherwise the scheme is the same as the one created with `try_new`.
+        pub fn try_new_with(forms: &[EncodingSchemeForm], mode: ValidationMode) -> Result<Self, SchemeValidationError> {
+            let _ = Self::validate_with(forms, mode)?;
+            match mode {
+                ValidationMode::Strict => Ok(Self(forms.to_vec(), ValidationMode::Strict)),
+                ValidationMode::Lenient => Ok(Self::from_valid_forms(forms.to_vec())),
             }
+        }
 
-            if forms.len() == 1 {
-                // if single form - prefix must be empty
-                let form = forms[0];
-                let (_, prefix_len, prefix) = form.params();
-                if prefix_len != 0 || prefix != 0 {
-                    return Err(SchemeValidationError::SingleFormWithPrefix);
-                }
+        /// Wraps forms already known to pass lenient validation, determining the strictest mode they pass.
+        fn from_valid_forms(forms: Vec<EncodingSchemeForm>) -> Self {
+            let mode = if Self::validate(&forms).is_ok() {
+                ValidationMode::Strict
+            } else {
+                ValidationMode::Lenient
+            };
+            Self(forms, mode)
+        }
+
+        /// The strictest set of rules this scheme passes: `ValidationMode::Strict` for schemes created with `try_new`,
+        /// and for those created in lenient mode which happen to pass strict validation as well.
+        pub fn validation_mode(&self) -> ValidationMode {
+            self.1
+        }
+
+        /// Forms of this encoding scheme.
+        pub fn forms(&self) -> &[EncodingSchemeForm] {
+            &self.0
+        }
+
+        /// Brings this scheme to canonical form in place, sorting forms by `bit_count`, then `prefix_len`, then `prefix`.
+        ///
+        /// Schemes with the same set of forms have the same canonical version, so it can be used to deduplicate
+        /// schemes collected from different nodes. Note that form numbers, which are indexes of forms in the scheme,
+        /// may differ between the original and the canonical scheme.
+        ///
+        /// Canonical version of a lenient scheme with unsorted forms passes strict validation, so its `validation_mode()`
+        /// becomes `ValidationMode::Strict`, unless it is a single form scheme with a prefix.
+        pub fn canonicalize(&mut self) {
+            let mut forms = core::mem::take(&mut self.0);
+            forms.sort();
+            *self = Self::from_valid_forms(forms);
+        }
+
+        /// Returns canonical version of this scheme, see `canonicalize()`.
+        pub fn canonical(&self) -> Self {
+            let mut scheme = self.clone();
+            scheme.canonicalize();
+            scheme
+        }
+
+        /// Checks if forms of this scheme are in canonical order, see `canonical()`.
+        pub fn is_canonical(&self) -> bool {
+            self.0.windows(2).all(|pair| pair[0] <= pair[1])
+        }
+
+        /// Store encoding scheme into a byte vector array (bits sequence).
+        ///
+        /// Unlike `serialize_scheme` function this method never fails,
+        /// because any successfully instantiated `EncodingScheme` is serializable.
+        ///
+        /// The result is parsed back into an equal scheme with `deserialize_scheme_with(bytes, self.validation_mode())`.
+        /// `deserialize_scheme` only accepts the result if `validation_mode()` is `ValidationMode::Strict`.
+        pub fn serialize(&self) -> Vec<u8> {
+            super::serialize_scheme(self).expect("valid scheme failed to serialize")
+        }
+
+        /// Stable numeric fingerprint of this scheme, e.g. to index or deduplicate schemes seen on the network.
+        ///
+        /// This is the 64-bit FNV-1a hash (offset basis `0xcbf29ce484222325`, prime `0x100000001b3`)
+        /// of `self.canonical().serialize()` bytes, so it is the same across runs and platforms,
+        /// and is the same for schemes which differ only in the order of forms.
+        /// Like any hash it may collide, so compare schemes themselves to tell them apart for sure.
+        pub fn fingerprint(&self) -> u64 {
+            const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+            const FNV_PRIME: u64 = 0x0100_0000_01b3;
+            let bytes = self.canonical().serialize();
+            bytes.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+        }
+
+        /// Length in bytes of this scheme serialized with `serialize()`, computed without serializing it.
+        ///
+        /// Each form takes 5 + 5 bits for `bit_count` and `prefix_len` plus `prefix_len` bits of prefix,
+        /// the last byte is zero-padded.
+        pub fn serialized_len(&self) -> usize {
+            let bits = self.0.iter().map(|form| 5 + 5 + form.prefix_len as usize).sum::<usize>();
+            (bits + 7) / 8
+        }
+
+        /// Checks that serialized scheme takes no more than `max_len` bytes, so it can be put into a node announcement
+        /// without wasting its size budget.
+        ///
+        /// Returns `SchemeValidationError::SerializedTooLong` otherwise.
+        pub fn validate_for_announcement(&self, max_len: usize) -> Result<(), SchemeValidationError> {
+            if self.serialized_len() > max_len {
+                return Err(SchemeValidationError::SerializedTooLong);
+            }
+            Ok(())
+        }
+
+        /// Number of distinct directors (next hops) which can be encoded with this scheme, the reserved one excluded.
+        ///
+        /// Every director encodable with a smaller form is also encodable with a bigger one, so this is the number
+        /// of directors of the largest form, `2^bit_count`, minus the one reserved for the node itself,
+        /// i.e. `usable_directors()` of the largest form. This is also the largest encodable director value.
+        /// Prefix bits don't take part in director encoding and thus don't affect this value.
+        pub fn max_directors(&self) -> u64 {
+            self.0.iter().map(|form| form.usable_directors()).max().expect("scheme has no forms")
+        }
+
+        /// Sum of `usable_directors()` across all forms of this scheme, i.e. the number of one-hop labels leading to a peer.
+        ///
+        /// A director encodable with a smaller form is encodable with a bigger one as well, so the same peer is usually
+        /// counted by several forms. Use `max_directors` for the number of distinct peers the scheme can address.
+        pub fn total_usable_directors(&self) -> u64 {
+            self.0.iter().map(|form| form.usable_directors()).sum()
+        }
+
+        /// Forms present in both this and `other` scheme, with the same `bit_count`, `prefix_len` and `prefix`,
+        /// in the order they go in this scheme.
+        ///
+        /// A hop encoded with a common form means the same to both nodes, e.g. when choosing how to encode a hop
+        /// across a link to a peer having `other` scheme. The result may be empty, and is not necessarily a valid scheme.
+        pub fn common_forms(&self, other: &EncodingScheme) -> Vec<EncodingSchemeForm> {
+            self.0.iter().filter(|form| other.0.contains(form)).copied().collect()
+        }
+
+        /// Lazily enumerates every one-hop label of this scheme: for each form, in order, every director
+        /// from `0` to `2^bit_count - 1` encoded with `EncodingSchemeForm::encode_director` and topped with the terminating `1` bit.
+        ///
+        /// Directors are encoded as is, with no special handling for `V358` scheme (see `splice::label_from_directors`).
+        /// Meant for tests and fuzzing, keep in mind a form with large `bit_count` alone yields up to `2^31` labels.
+        #[cfg(feature = "std")]
+        pub fn one_hop_labels(&self) -> impl Iterator<Item = DefaultRoutingLabel> + '_ {
+            self.0.iter().flat_map(|form| {
+                (0..1_u64 << form.bit_count).map(move |director| {
+                    let encoded = form.encode_director(director).expect("director fits the form");
+                    DefaultRoutingLabel::try_new((1 << form.size_bits()) | encoded).expect("zero label")
+                })
+            })
+        }
+
+        /// Finds the form which is used to encode a director, given the label (or its least significant bits)
+        /// containing the director.
+        ///
+        /// For a multi-form scheme the form is found by matching the label's `prefix_len` least significant bits against
+        /// the form's `prefix`, for a single-form scheme the only form is returned. Returns `None` if no form matches.
+        ///
+        /// See also `splice::get_encoding_form` which does the same for `RoutingLabel`.
+        pub fn form_for_director(&self, director: u64) -> Option<&EncodingSchemeForm> {
+            self.0.iter().find(|form| {
+                let prefix_mask = (1_u64 << form.prefix_len) - 1;
+                director & prefix_mask == form.prefix as u64
+            })
+        }
+
+        /// Finds the most compact form which can encode `director`, i.e. the form with the least `bit_count`
+        /// having enough director bits to hold it, the first one of such forms if there are several.
+        /// Returns `None` if the director is too big for any form of this scheme.
+        ///
+        /// This is the encoding counterpart of `form_for_director`. Use `EncodingSchemeForm::encode_director`
+        /// to actually encode the director with the found form.
+        pub fn smallest_form_for(&self, director: u64) -> Option<&EncodingSchemeForm> {
+            let director_bits = 64 - director.leading_zeros();
+            self.0
+                .iter()
+                .filter(|form| form.bit_count as u32 >= director_bits)
+                .min_by_key(|form| form.bit_count)
+        }
+
+        /// Renders this scheme the same way cjdns does in its logs, e.g. for `V48`:
+        ///
+        /// ```text
+        /// [{ bitCount: 4, prefix: "1", prefixLen: 1 }, { bitCount: 8, prefix: "0", prefixLen: 1 }]
+        /// ```
+        ///
+        /// Prefix is shown as a binary string zero-padded to `prefix_len` digits,
+        /// so it is empty for the only form of a single-form scheme.
+        pub fn describe(&self) -> String {
+            let forms = self
+                .0
+                .iter()
+                .map(|form| {
+                    let prefix = if form.prefix_len == 0 {
+                        String::new()
+                    } else {
+                        format!("{:0width$b}", form.prefix, width = form.prefix_len as usize)
+                    };
+                    format!("{{ bitCount: {}, prefix: \"{}\", prefixLen: {} }}", form.bit_count, prefix, form.prefix_len)
+                })
+                .collect::<Vec<_>>();
+            format!("[{}]", forms.join(", "))
+        }
+
+        /// Minimal amount of label bits occupied by a single hop encoded with this scheme,
+        /// i.e. the smallest `size_bits` (director bits plus prefix bits) among scheme forms.
+        pub fn min_form_size(&self) -> u8 {
+            self.0.iter().map(|form| form.size_bits()).min().expect("scheme has no forms")
+        }
+
+        /// Validates encoding scheme.
+        ///
+        /// Returns an error in several situations:
+        /// * provided forms slice length is 0 or greater than 31
+        /// * `bit_count` value of any form is out of valid range - 1..32
+        /// * `prefix_len` value of any form is out if valid range - 1..32 (for multiple forms scheme)
+        /// * `prefix` value of any form doesn't fit in `prefix_len` bits (for multiple forms scheme)
+        /// * forms are not in ascending order by `bits_count` key
+        /// * bits size of a form is greater than 59 (for multiple forms scheme)
+        /// * forms with equal prefixes are in scheme
+        /// * prefix of a form equals to lower bits of another form's prefix, so the forms can't be told apart
+        /// * the only form of a single form scheme has a prefix
+        ///
+        /// Each returned value fully reflects error type.
+        ///
+        /// This is `validate_with(forms, ValidationMode::Strict)`, all instances of `EncodingScheme` created with `try_new`
+        /// are valid this way.
+        pub fn validate(forms: &[EncodingSchemeForm]) -> Result<(), SchemeValidationError> {
+            Self::validate_with(forms, ValidationMode::Strict)
+        }
+
+        /// Validates encoding scheme with the given set of rules, see `ValidationMode`.
+        pub fn validate_with(forms: &[EncodingSchemeForm], mode: ValidationMode) -> Result<(), SchemeValidationError> {
+            // each form must have a different prefix_len and bit_count;
+            // can only be expressed in 5 bits limiting it to 31 bits max and a form
+            // using zero bits is not allowed so there are only 31 max possibilities.
+            if forms.len() == 0 || forms.len() > Self::MAX_FORMS {
+                return Err(SchemeValidationError::InvalidFormsAmount);
+            }
+
+            if forms.len() == 1 {
+                if mode == ValidationMode::Lenient {
+                    return Ok(());
+                }
+                // if single form - prefix must be empty
+                let form = forms[0];
+                let (_, prefix_len, prefix) = form.params();
+                if prefix_len != 0 || prefix != 0 {
+                    return Err(SchemeValidationError::SingleFormWithPrefix);
+                }
                 return Ok(());
             }
 
             let mut last_bit_count = 0;
-            let mut used_prefixes = HashSet::new();
 
-            for form in forms {
+            for (i, form) in forms.iter().enumerate() {
                 let (bit_count, prefix_len, prefix) = form.params();
                 // when multiple forms - prefixes must be non-empty
                 if prefix_len == 0 || prefix_len > 31 {
                     return Err(SchemeValidationError::MultiFormBadPrefix);
                 }
 
+                // prefix must fit in `prefix_len` bits, otherwise it gets truncated on serialization
+                if prefix >> prefix_len != 0 {
+                    return Err(SchemeValidationError::PrefixTooLong);
+                }
+
                 // forms must have bit_count in ascending order
-                if last_bit_count > bit_count {
+                if mode == ValidationMode::Strict && last_bit_count > bit_count {
                     return Err(SchemeValidationError::BitCountNotSorted);
                 }
                 last_bit_count = bit_count;
@@ -428,16 +1440,53 @@ mod encoding_scheme {
                     return Err(SchemeValidationError::TooBigForm);
                 }
 
-                // forms must be distinguishable by their prefix
-                if used_prefixes.contains(&prefix) {
+                // forms must be distinguishable by their prefix;
+                // there are at most 31 forms, so a linear scan is cheap and needs no allocation
+                if forms[..i].iter().any(|prev_form| prev_form.prefix == prefix) {
                     return Err(SchemeValidationError::DuplicatePrefix);
                 }
-                used_prefixes.insert(prefix);
+            }
+
+            // prefixes are read from the least significant bits of a label,
+            // so no prefix may be the lower bits of another one
+            for (i, form) in forms.iter().enumerate() {
+                for other_form in &forms[i + 1..] {
+                    let common_len = form.prefix_len.min(other_form.prefix_len);
+                    let common_mask = (1_u32 << common_len) - 1;
+                    if form.prefix & common_mask == other_form.prefix & common_mask {
+                        return Err(SchemeValidationError::AmbiguousPrefixes);
+                    }
+                }
             }
             Ok(())
         }
     }
 
+    #[cfg(feature = "serde_json")]
+    impl EncodingScheme {
+        /// Parses encoding scheme the way cjdns admin API returns it, i.e. as a JSON array of forms
+        /// like `[{"bitCount": 3, "prefixLen": 1, "prefix": 1}, ...]`, where `prefix` is a decimal number.
+        ///
+        /// Returns `AdminJsonSchemeError::Malformed` if the value doesn't have that structure,
+        /// otherwise an error describing why forms or the scheme itself are invalid.
+        pub fn from_admin_json(value: &serde_json::Value) -> Result<Self, AdminJsonSchemeError> {
+            fn field(form: &serde_json::Map<String, serde_json::Value>, name: &'static str) -> Result<u64, AdminJsonSchemeError> {
+                form.get(name).and_then(|value| value.as_u64()).ok_or(AdminJsonSchemeError::Malformed(name))
+            }
+
+            let forms_json = value.as_array().ok_or(AdminJsonSchemeError::Malformed("scheme is not an array"))?;
+            let mut forms = Vec::with_capacity(forms_json.len());
+            for form_json in forms_json {
+                let form_json = form_json.as_object().ok_or(AdminJsonSchemeError::Malformed("form is not an object"))?;
+                let bit_count = u8::try_from(field(form_json, "bitCount")?).or(Err(AdminJsonSchemeError::InvalidForm(FormValidationError::BadBitCount)))?;
+                let prefix_len = u8::try_from(field(form_json, "prefixLen")?).or(Err(AdminJsonSchemeError::InvalidForm(FormValidationError::BadPrefixLen)))?;
+                let prefix = u32::try_from(field(form_json, "prefix")?).or(Err(AdminJsonSchemeError::InvalidForm(FormValidationError::InvalidPrefixData)))?;
+                forms.push(EncodingSchemeForm::try_new(bit_count, prefix_len, prefix).map_err(AdminJsonSchemeError::InvalidForm)?);
+            }
+            Self::try_new(&forms).map_err(AdminJsonSchemeError::InvalidScheme)
+        }
+    }
+
     impl Deref for EncodingScheme {
         type Target = [EncodingSchemeForm];
 
@@ -446,6 +1495,119 @@ mod encoding_scheme {
         }
     }
 
+    /// Set of rules to check by `EncodingScheme::validate_with`.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum ValidationMode {
+        /// All the rules listed in `EncodingScheme::validate`.
+        Strict,
+
+        /// Only the rules a scheme must follow to be decodable and serializable, i.e. all the rules except that:
+        /// * forms must be in ascending order by `bit_count`
+        /// * the only form of a single form scheme must have no prefix
+        ///
+        /// Such scheme is instantiated with `EncodingScheme::try_new_with` or `deserialize_scheme_with`, and unless its forms
+        /// pass strict validation as well, is marked with this mode, see `EncodingScheme::validation_mode`. It decodes labels
+        /// the same way a strict one does, since forms are told apart by prefixes, and encodes directors with the same
+        /// forms too, as forms are picked by `bit_count` rather than by position.
+        /// Sorting its forms (see `EncodingSchemeForm` ordering) makes it valid in strict mode,
+        /// unless it is a single form scheme with a prefix.
+        Lenient,
+    }
+
+    /// Builds `EncodingScheme` form by form, validating each form as it is added.
+    ///
+    /// Unlike `EncodingScheme::try_new`, which validates all forms at once, this reports an error on the very form
+    /// which breaks the rules of `EncodingScheme::validate`. Rejected forms are not added, so building can go on.
+    ///
+    /// ```rust
+    /// # use cjdns_core::{EncodingScheme, EncodingSchemeBuilder, EncodingSchemeForm, SchemeValidationError};
+    /// # let encoding_form = |bit_count, prefix_len, prefix| EncodingSchemeForm::try_new(bit_count, prefix_len, prefix).expect("invalid encoding form");
+    /// let mut builder = EncodingSchemeBuilder::new();
+    /// builder.push(encoding_form(4, 1, 1))?.push(encoding_form(8, 1, 0))?;
+    /// assert_eq!(builder.push(encoding_form(6, 2, 0b10)).err(), Some(SchemeValidationError::BitCountNotSorted));
+    /// assert_eq!(builder.build(), EncodingScheme::try_new(&[encoding_form(4, 1, 1), encoding_form(8, 1, 0)]));
+    /// # Ok::<(), SchemeValidationError>(())
+    /// ```
+    #[derive(Debug, Clone, Default)]
+    pub struct EncodingSchemeBuilder {
+        forms: Vec<EncodingSchemeForm>,
+    }
+
+    impl EncodingSchemeBuilder {
+        /// Creates a builder with no forms, same as `default()`.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Adds a form, checking it against the forms added before.
+        ///
+        /// A single form can't be checked on its own, as the rules for single and multiple form schemes differ,
+        /// so the first form is checked when the second one is added, or on `build`.
+        pub fn push(&mut self, form: EncodingSchemeForm) -> Result<&mut Self, SchemeValidationError> {
+            self.forms.push(form);
+            // for two and more forms every rule holds for a subset of forms if it holds for all of them,
+            // so validating all forms added so far finds only the errors caused by the new form
+            if self.forms.len() > 1 {
+                if let Err(e) = EncodingScheme::validate(&self.forms) {
+                    self.forms.pop();
+                    return Err(e);
+                }
+            }
+            Ok(self)
+        }
+
+        /// Forms added so far.
+        pub fn forms(&self) -> &[EncodingSchemeForm] {
+            &self.forms
+        }
+
+        /// Builds the scheme, running the checks which need all the forms, e.g. the amount of forms.
+        pub fn build(&self) -> Result<EncodingScheme, SchemeValidationError> {
+            EncodingScheme::try_new(&self.forms)
+        }
+    }
+
+    /// Parses a scheme from a compact spec string, which is either a name of a well-known scheme, as in `schemes::scheme_by_name`,
+    /// or a comma separated list of `bit_count/prefix_len/prefix` decimal triples, one per form.
+    ///
+    /// ```rust
+    /// # use cjdns_core::{schemes, EncodingScheme};
+    /// assert_eq!("v358".parse::<EncodingScheme>(), Ok(schemes::V358.clone()));
+    /// assert_eq!("3/1/1, 5/2/2, 8/2/0".parse::<EncodingScheme>(), Ok(schemes::V358.clone()));
+    /// ```
+    ///
+    /// The resulting scheme is validated as in `EncodingScheme::try_new`.
+    #[cfg(feature = "std")]
+    impl FromStr for EncodingScheme {
+        type Err = SchemeParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let s = s.trim();
+            if s.is_empty() {
+                return Err(SchemeParseError::Empty);
+            }
+            if !s.contains('/') {
+                return schemes::scheme_by_name(s).cloned().ok_or_else(|| SchemeParseError::UnknownName(s.to_string()));
+            }
+
+            let mut forms = Vec::new();
+            for (index, form_str) in s.split(',').enumerate() {
+                let params = form_str.trim().split('/').collect::<Vec<_>>();
+                let (bit_count, prefix_len, prefix) = match params.as_slice() {
+                    [bit_count, prefix_len, prefix] => match (bit_count.parse(), prefix_len.parse(), prefix.parse()) {
+                        (Ok(bit_count), Ok(prefix_len), Ok(prefix)) => (bit_count, prefix_len, prefix),
+                        _ => return Err(SchemeParseError::MalformedForm(index)),
+                    },
+                    _ => return Err(SchemeParseError::MalformedForm(index)),
+                };
+                let form = EncodingSchemeForm::try_new(bit_count, prefix_len, prefix).map_err(|err| SchemeParseError::InvalidForm(index, err))?;
+                forms.push(form);
+            }
+            EncodingScheme::try_new(&forms).map_err(SchemeParseError::InvalidScheme)
+        }
+    }
+
+    #[cfg(feature = "std")]
     pub mod schemes {
         //! Well-known encoding schemes
 
@@ -524,14 +1686,32 @@ mod encoding_scheme {
             ALL.iter()
         }
 
+        /// Looks up a well-known encoding scheme by its name.
+        ///
+        /// The name is case-insensitive and may have the `SCHEME_` prefix used in cjdns sources,
+        /// so `"v358"`, `"V358"` and `"SCHEME_v358"` all refer to `V358` scheme.
+        pub fn scheme_by_name(name: &str) -> Option<&'static EncodingScheme> {
+            let name = name.to_ascii_lowercase();
+            let name = name.strip_prefix("scheme_").unwrap_or(&name);
+            match name {
+                "f4" => Some(&*F4),
+                "f8" => Some(&*F8),
+                "v48" => Some(&*V48),
+                "v358" => Some(&*V358),
+                "v37" => Some(&*V37),
+                _ => None,
+            }
+        }
+
         fn encoding_scheme(forms: &[EncodingSchemeForm]) -> EncodingScheme {
             EncodingScheme::try_new(forms).expect("invalid form")
         }
     }
 
-    #[cfg(test)]
+    #[cfg(all(test, feature = "std"))]
     mod tests {
-        use super::{schemes, EncodingScheme, EncodingSchemeForm};
+        use super::{form_size, schemes, EncodingScheme, EncodingSchemeBuilder, EncodingSchemeForm};
+        use crate::{serialize_scheme_hex, FormValidationError, SchemeParseError, SchemeValidationError};
 
         fn encoding_scheme(forms: &[EncodingSchemeForm]) -> EncodingScheme {
             EncodingScheme::try_new(forms).expect("invalid scheme")
@@ -552,6 +1732,557 @@ mod encoding_scheme {
             );
         }
 
+        #[test]
+        fn encoding_form_try_new() {
+            assert_eq!(EncodingSchemeForm::try_new(4, 1, 1), Ok(encoding_form(4, 1, 1)));
+            assert_eq!(EncodingSchemeForm::try_new(31, 31, 0x7fff_ffff), Ok(encoding_form(31, 31, 0x7fff_ffff)));
+            assert_eq!(EncodingSchemeForm::try_new(0, 0, 0), Err(FormValidationError::BadBitCount));
+            assert_eq!(EncodingSchemeForm::try_new(34, 0, 0), Err(FormValidationError::BadBitCount));
+            assert_eq!(EncodingSchemeForm::try_new(4, 32, 0), Err(FormValidationError::BadPrefixLen));
+            assert_eq!(EncodingSchemeForm::try_new(4, 40, 1), Err(FormValidationError::BadPrefixLen));
+            assert_eq!(EncodingSchemeForm::try_new(4, 255, 0), Err(FormValidationError::BadPrefixLen));
+            assert_eq!(EncodingSchemeForm::try_new(4, 1, 2), Err(FormValidationError::InvalidPrefixData));
+        }
+
+        #[test]
+        fn encoding_form_directors() {
+            let form = encoding_form(5, 2, 0b10);
+            assert_eq!(form.encode_director(0), Ok(0b0000010));
+            assert_eq!(form.encode_director(0b10011), Ok(0b1001110));
+            assert_eq!(form.encode_director(0b100000), Err(FormValidationError::DirectorTooBig));
+            assert_eq!(form.decode_director(0b1001110), 0b10011);
+            // bits above the director are ignored
+            assert_eq!(form.decode_director(0b1_1001110), 0b10011);
+
+            assert_eq!(encoding_form(8, 0, 0).encode_director(0x13), Ok(0x13));
+            assert_eq!(encoding_form(8, 0, 0).decode_director(0x113), 0x13);
+            assert_eq!(encoding_form(31, 31, 0x7fff_ffff).encode_director(0x7fff_ffff), Ok(u64::MAX >> 2));
+            assert_eq!(encoding_form(31, 31, 0x7fff_ffff).decode_director(u64::MAX), 0x7fff_ffff);
+
+            for scheme in schemes::all() {
+                for form in scheme.iter() {
+                    for director in 0..(1 << form.bit_count) {
+                        let bits = form.encode_director(director).expect("director doesn't fit");
+                        assert_eq!(scheme.form_for_director(bits), Some(form));
+                        assert_eq!(form.decode_director(bits), director);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn encoding_scheme_validation_mode() {
+            use super::ValidationMode::{Lenient, Strict};
+
+            for scheme in schemes::all() {
+                assert_eq!(EncodingScheme::validate_with(scheme, Strict), Ok(()));
+                assert_eq!(EncodingScheme::validate_with(scheme, Lenient), Ok(()));
+                assert_eq!(scheme.validation_mode(), Strict);
+                // valid in strict mode, so the same as created with `try_new`
+                assert_eq!(EncodingScheme::try_new_with(scheme, Lenient).as_ref(), Ok(scheme));
+            }
+
+            let unsorted = [encoding_form(8, 2, 0), encoding_form(3, 1, 1), encoding_form(5, 2, 2)];
+            assert_eq!(EncodingScheme::validate(&unsorted), Err(SchemeValidationError::BitCountNotSorted));
+            assert_eq!(EncodingScheme::validate_with(&unsorted, Strict), Err(SchemeValidationError::BitCountNotSorted));
+            assert_eq!(EncodingScheme::validate_with(&unsorted, Lenient), Ok(()));
+            assert_eq!(EncodingScheme::try_new_with(&unsorted, Strict), Err(SchemeValidationError::BitCountNotSorted));
+            let scheme = EncodingScheme::try_new_with(&unsorted, Lenient).expect("lenient scheme rejected");
+            assert_eq!(&*scheme, &unsorted[..]);
+            assert_eq!(scheme.validation_mode(), Lenient);
+            assert!(!scheme.is_canonical());
+            assert_eq!(scheme.canonical().validation_mode(), Strict);
+            assert_eq!(EncodingScheme::try_new(&scheme.canonical()), Ok(scheme.canonical()));
+            assert_eq!(scheme.fingerprint(), schemes::V358.fingerprint());
+            assert_eq!(scheme.smallest_form_for(0x1f), Some(&unsorted[2]));
+            assert_eq!(scheme.smallest_form_for(0x20), Some(&unsorted[0]));
+            let serialized = scheme.serialize();
+            assert_eq!(
+                crate::deserialize_scheme(&serialized),
+                Err(crate::EncodingSerializationError::BadSerializedData)
+            );
+            assert_eq!(crate::deserialize_scheme_with(&serialized, scheme.validation_mode()), Ok(scheme.clone()));
+            assert_eq!(crate::deserialize_scheme_with(&serialized, Lenient).map(|s| s.validation_mode()), Ok(Lenient));
+
+            let single_with_prefix = [encoding_form(4, 1, 1)];
+            assert_eq!(
+                EncodingScheme::validate_with(&single_with_prefix, Strict),
+                Err(SchemeValidationError::SingleFormWithPrefix)
+            );
+            assert_eq!(EncodingScheme::validate_with(&single_with_prefix, Lenient), Ok(()));
+            let scheme = EncodingScheme::try_new_with(&single_with_prefix, Lenient).expect("lenient scheme rejected");
+            assert_eq!(scheme.validation_mode(), Lenient);
+            assert_eq!(scheme.canonical(), scheme);
+            assert_eq!(
+                crate::deserialize_scheme(&scheme.serialize()),
+                Err(crate::EncodingSerializationError::BadSerializedData)
+            );
+            assert_eq!(crate::deserialize_scheme_with(&scheme.serialize(), scheme.validation_mode()), Ok(scheme));
+
+            // undecodable schemes are rejected in both modes
+            let broken_cases = [
+                (vec![], SchemeValidationError::InvalidFormsAmount),
+                (vec![encoding_form(8, 0, 0), encoding_form(4, 1, 1)], SchemeValidationError::MultiFormBadPrefix),
+                (vec![encoding_form(8, 1, 0), encoding_form(4, 1, 0b11)], SchemeValidationError::PrefixTooLong),
+                (vec![encoding_form(8, 1, 1), encoding_form(4, 1, 1)], SchemeValidationError::DuplicatePrefix),
+                (
+                    vec![encoding_form(8, 2, 0b11), encoding_form(4, 1, 1)],
+                    SchemeValidationError::AmbiguousPrefixes,
+                ),
+                (vec![encoding_form(31, 30, 0), encoding_form(4, 30, 1)], SchemeValidationError::TooBigForm),
+            ];
+            for (forms, err) in broken_cases.iter() {
+                assert_eq!(EncodingScheme::validate_with(forms, Lenient).as_ref(), Err(err));
+            }
+        }
+
+        #[test]
+        fn encoding_scheme_prefix_too_long() {
+            // such forms can't be made with `EncodingSchemeForm::try_new`
+            assert_eq!(
+                EncodingScheme::try_new(&[encoding_form(4, 1, 0b111), encoding_form(8, 1, 0)]),
+                Err(SchemeValidationError::PrefixTooLong)
+            );
+            assert_eq!(
+                EncodingScheme::try_new(&[encoding_form(4, 2, 0b01), encoding_form(8, 2, 0b100)]),
+                Err(SchemeValidationError::PrefixTooLong)
+            );
+        }
+
+        #[test]
+        fn encoding_scheme_form_for_director() {
+            assert_eq!(schemes::F8.form_for_director(0x13), Some(&encoding_form(8, 0, 0)));
+            assert_eq!(schemes::V358.form_for_director(0x13), Some(&encoding_form(3, 1, 1)));
+            assert_eq!(schemes::V358.form_for_director(0x1110), Some(&encoding_form(8, 2, 0)));
+            assert_eq!(schemes::V358.form_for_director(0b1010), Some(&encoding_form(5, 2, 2)));
+            assert_eq!(schemes::V48.form_for_director(0), Some(&encoding_form(8, 1, 0)));
+
+            let scheme = encoding_scheme(&[encoding_form(4, 2, 0b01), encoding_form(8, 2, 0b00)]);
+            assert_eq!(scheme.form_for_director(0b10), None);
+            assert_eq!(scheme.form_for_director(0b11), None);
+        }
+
+        #[test]
+        fn encoding_scheme_smallest_form_for() {
+            assert_eq!(schemes::F8.smallest_form_for(0), Some(&encoding_form(8, 0, 0)));
+            assert_eq!(schemes::F8.smallest_form_for(0xff), Some(&encoding_form(8, 0, 0)));
+            assert_eq!(schemes::F8.smallest_form_for(0x100), None);
+
+            assert_eq!(schemes::V358.smallest_form_for(0), Some(&encoding_form(3, 1, 1)));
+            assert_eq!(schemes::V358.smallest_form_for(7), Some(&encoding_form(3, 1, 1)));
+            assert_eq!(schemes::V358.smallest_form_for(8), Some(&encoding_form(5, 2, 2)));
+            assert_eq!(schemes::V358.smallest_form_for(0x1f), Some(&encoding_form(5, 2, 2)));
+            assert_eq!(schemes::V358.smallest_form_for(0x20), Some(&encoding_form(8, 2, 0)));
+            assert_eq!(schemes::V358.smallest_form_for(0xff), Some(&encoding_form(8, 2, 0)));
+            assert_eq!(schemes::V358.smallest_form_for(0x100), None);
+            assert_eq!(schemes::V358.smallest_form_for(u64::MAX), None);
+
+            for scheme in schemes::all() {
+                for director in 0..=scheme.max_directors() {
+                    let form = scheme.smallest_form_for(director).expect("no form for encodable director");
+                    let bits = form.encode_director(director).expect("director doesn't fit the form");
+                    assert_eq!(scheme.form_for_director(bits), Some(form));
+                    assert_eq!(form.decode_director(bits), director);
+                }
+                assert_eq!(scheme.smallest_form_for(scheme.max_directors() + 1), None);
+            }
+        }
+
+        #[test]
+        fn encoding_scheme_canonical() {
+            for scheme in schemes::all() {
+                assert!(scheme.is_canonical());
+                assert_eq!(&scheme.canonical(), scheme);
+            }
+
+            let scheme1 = encoding_scheme(&[encoding_form(4, 2, 0b01), encoding_form(4, 2, 0b10), encoding_form(8, 2, 0b00)]);
+            let scheme2 = encoding_scheme(&[encoding_form(4, 2, 0b10), encoding_form(4, 2, 0b01), encoding_form(8, 2, 0b00)]);
+            assert_ne!(scheme1, scheme2);
+            assert!(scheme1.is_canonical());
+            assert!(!scheme2.is_canonical());
+            assert_eq!(scheme1.canonical(), scheme2.canonical());
+            assert_eq!(scheme2.canonical(), scheme1);
+            assert!(scheme2.canonical().is_canonical());
+
+            let scheme = encoding_scheme(&[encoding_form(4, 3, 0b001), encoding_form(4, 1, 0b0)]);
+            assert!(!scheme.is_canonical());
+            assert_eq!(scheme.canonical().forms(), &[encoding_form(4, 1, 0b0), encoding_form(4, 3, 0b001)]);
+
+            let mut scheme = scheme2.clone();
+            scheme.canonicalize();
+            assert_eq!(scheme, scheme2.canonical());
+            scheme.canonicalize();
+            assert_eq!(scheme, scheme1);
+        }
+
+        #[test]
+        fn encoding_form_size() {
+            const FORM: EncodingSchemeForm = match EncodingSchemeForm::try_new(5, 2, 0b10) {
+                Ok(form) => form,
+                Err(_) => panic!("invalid scheme form"),
+            };
+            const SIZES: [u8; 2] = [form_size(FORM), FORM.size_bits()];
+            assert_eq!(SIZES, [7, 7]);
+            assert_eq!(FORM.params(), (5, 2, 0b10));
+
+            for scheme in schemes::all() {
+                for form in scheme.iter() {
+                    assert_eq!(form_size(*form), form.size_bits());
+                    assert_eq!(form_size(*form), form.params().0 + form.params().1);
+                }
+            }
+        }
+
+        #[test]
+        fn encoding_form_setters() {
+            let mut form = encoding_form(4, 2, 0b01);
+            assert_eq!(form.set_prefix(0b11), Ok(()));
+            assert_eq!(form, encoding_form(4, 2, 0b11));
+            assert_eq!(form.set_prefix(0b100), Err(FormValidationError::InvalidPrefixData));
+            assert_eq!(form, encoding_form(4, 2, 0b11));
+
+            assert_eq!(form.set_prefix_len(1), Err(FormValidationError::InvalidPrefixData));
+            assert_eq!(form.set_prefix_len(32), Err(FormValidationError::BadPrefixLen));
+            assert_eq!(form, encoding_form(4, 2, 0b11));
+            assert_eq!(form.set_prefix_len(5), Ok(()));
+            assert_eq!(form, encoding_form(4, 5, 0b11));
+
+            assert_eq!(form.set_prefix(0), Ok(()));
+            assert_eq!(form.set_prefix_len(0), Ok(()));
+            assert_eq!(form, encoding_form(4, 0, 0));
+            assert_eq!(form.set_prefix(1), Err(FormValidationError::InvalidPrefixData));
+        }
+
+        #[test]
+        fn encoding_form_ordering() {
+            assert!(encoding_form(4, 3, 0b111) < encoding_form(5, 1, 0b0));
+            assert!(encoding_form(4, 1, 0b1) < encoding_form(4, 2, 0b00));
+            assert!(encoding_form(4, 2, 0b01) < encoding_form(4, 2, 0b10));
+
+            for scheme in schemes::all() {
+                let mut forms = scheme.forms().to_vec();
+                forms.reverse();
+                forms.rotate_left(1);
+                forms.sort();
+                assert_eq!(EncodingScheme::validate(&forms), Ok(()));
+                assert_eq!(&encoding_scheme(&forms), scheme);
+            }
+
+            let mut forms = vec![
+                encoding_form(8, 2, 0b00),
+                encoding_form(4, 2, 0b10),
+                encoding_form(4, 2, 0b01),
+                encoding_form(3, 2, 0b11),
+            ];
+            assert_eq!(EncodingScheme::validate(&forms), Err(SchemeValidationError::BitCountNotSorted));
+            forms.sort();
+            assert_eq!(EncodingScheme::validate(&forms), Ok(()));
+            assert_eq!(
+                forms,
+                [
+                    encoding_form(3, 2, 0b11),
+                    encoding_form(4, 2, 0b01),
+                    encoding_form(4, 2, 0b10),
+                    encoding_form(8, 2, 0b00)
+                ]
+            );
+        }
+
+        #[test]
+        fn encoding_scheme_builder() {
+            for scheme in schemes::all() {
+                let mut builder = EncodingSchemeBuilder::new();
+                for &form in scheme.forms() {
+                    builder.push(form).expect("valid form rejected");
+                }
+                assert_eq!(builder.build().as_ref(), Ok(scheme));
+            }
+
+            assert_eq!(EncodingSchemeBuilder::new().build(), Err(SchemeValidationError::InvalidFormsAmount));
+
+            let mut builder = EncodingSchemeBuilder::new();
+            builder.push(encoding_form(4, 1, 1)).expect("valid form rejected");
+            assert_eq!(builder.build(), Err(SchemeValidationError::SingleFormWithPrefix));
+            assert_eq!(builder.push(encoding_form(3, 1, 0)).err(), Some(SchemeValidationError::BitCountNotSorted));
+            assert_eq!(builder.push(encoding_form(5, 1, 1)).err(), Some(SchemeValidationError::DuplicatePrefix));
+            assert_eq!(builder.push(encoding_form(5, 2, 0b11)).err(), Some(SchemeValidationError::AmbiguousPrefixes));
+            assert_eq!(builder.push(encoding_form(5, 0, 0)).err(), Some(SchemeValidationError::MultiFormBadPrefix));
+            assert_eq!(builder.forms(), &[encoding_form(4, 1, 1)]);
+            builder.push(encoding_form(8, 1, 0)).expect("valid form rejected");
+            assert_eq!(builder.build(), Ok(schemes::V48.clone()));
+
+            let mut builder = EncodingSchemeBuilder::new();
+            for i in 0..EncodingScheme::MAX_FORMS as u32 {
+                builder.push(encoding_form(1, 31, 0x7fff_ffff - i)).expect("valid form rejected");
+            }
+            assert_eq!(builder.push(encoding_form(1, 31, 0)).err(), Some(SchemeValidationError::InvalidFormsAmount));
+            assert_eq!(builder.build().map(|scheme| scheme.len()), Ok(EncodingScheme::MAX_FORMS));
+        }
+
+        #[test]
+        fn encoding_scheme_fingerprint() {
+            assert_eq!(schemes::F8.fingerprint(), 0x0832_8707_b4eb_6e3a);
+            assert_eq!(schemes::V48.fingerprint(), 0x2538_571a_db03_ee38);
+            assert_eq!(schemes::V358.fingerprint(), 0xf04a_2361_3c45_a206);
+
+            let scheme1 = encoding_scheme(&[encoding_form(4, 2, 0b01), encoding_form(4, 2, 0b10), encoding_form(8, 2, 0b00)]);
+            let scheme2 = encoding_scheme(&[encoding_form(4, 2, 0b10), encoding_form(4, 2, 0b01), encoding_form(8, 2, 0b00)]);
+            assert_eq!(scheme1.fingerprint(), scheme2.fingerprint());
+
+            let fingerprints = schemes::all().map(|scheme| scheme.fingerprint()).collect::<std::collections::HashSet<_>>();
+            assert_eq!(fingerprints.len(), schemes::all().count());
+        }
+
+        #[test]
+        fn encoding_scheme_forms() {
+            let forms = [encoding_form(4, 1, 1), encoding_form(8, 1, 0)];
+            let scheme = encoding_scheme(&forms);
+            assert_eq!(scheme.forms(), &forms);
+            assert_eq!(scheme.serialize(), [0x81, 0x0c, 0x08].to_vec());
+        }
+
+        #[test]
+        fn encoding_scheme_serialized_len() {
+            for scheme in schemes::all() {
+                assert_eq!(scheme.serialized_len(), scheme.serialize().len());
+            }
+            assert_eq!(schemes::V358.serialized_len(), 5);
+
+            let forms = (0..31).map(|i| encoding_form(1, 31, 0x7fff_ffff - i)).collect::<Vec<_>>();
+            let scheme = encoding_scheme(&forms);
+            assert_eq!(scheme.serialized_len(), EncodingScheme::MAX_SERIALIZED_LEN);
+            assert_eq!(scheme.serialized_len(), scheme.serialize().len());
+
+            assert_eq!(schemes::V358.validate_for_announcement(5), Ok(()));
+            assert_eq!(schemes::V358.validate_for_announcement(100), Ok(()));
+            assert_eq!(schemes::V358.validate_for_announcement(4), Err(SchemeValidationError::SerializedTooLong));
+            assert_eq!(scheme.validate_for_announcement(64), Err(SchemeValidationError::SerializedTooLong));
+        }
+
+        #[test]
+        fn encoding_scheme_capacity() {
+            assert_eq!(schemes::F4.max_directors(), 15);
+            assert_eq!(schemes::F4.min_form_size(), 4);
+            assert_eq!(schemes::F8.max_directors(), 255);
+            assert_eq!(schemes::F8.min_form_size(), 8);
+            assert_eq!(schemes::V48.max_directors(), 255);
+            assert_eq!(schemes::V48.min_form_size(), 5);
+            assert_eq!(schemes::V358.max_directors(), 255);
+            assert_eq!(schemes::V358.min_form_size(), 4);
+            assert_eq!(schemes::V37.max_directors(), 127);
+            assert_eq!(schemes::V37.min_form_size(), 4);
+
+            let scheme = encoding_scheme(&[encoding_form(1, 31, 0), encoding_form(28, 31, 1), encoding_form(31, 28, 2)]);
+            assert_eq!(scheme.max_directors(), (1 << 31) - 1);
+            assert_eq!(scheme.min_form_size(), 32);
+            assert_eq!(scheme.total_usable_directors(), 1 + ((1 << 28) - 1) + ((1 << 31) - 1));
+        }
+
+        #[test]
+        fn encoding_scheme_usable_directors() {
+            assert_eq!(encoding_form(1, 0, 0).usable_directors(), 1);
+            assert_eq!(encoding_form(3, 1, 1).usable_directors(), 7);
+            assert_eq!(encoding_form(8, 2, 0).usable_directors(), 255);
+            assert_eq!(encoding_form(31, 1, 0).usable_directors(), 0x7fff_ffff);
+
+            assert_eq!(schemes::F4.total_usable_directors(), 15);
+            assert_eq!(schemes::F8.total_usable_directors(), 255);
+            assert_eq!(schemes::V358.total_usable_directors(), 7 + 31 + 255);
+            assert_eq!(schemes::V48.total_usable_directors(), 15 + 255);
+            assert_eq!(schemes::V37.total_usable_directors(), 7 + 127);
+
+            // distinct peers are counted by the largest form alone
+            assert_eq!(schemes::V358.max_directors(), schemes::V358[2].usable_directors());
+            assert_eq!(schemes::V48.max_directors(), schemes::V48[1].usable_directors());
+            for scheme in schemes::all() {
+                assert!(scheme.total_usable_directors() >= scheme.max_directors());
+                assert_eq!(scheme.total_usable_directors() == scheme.max_directors(), scheme.len() == 1);
+            }
+        }
+
+        #[test]
+        fn encoding_scheme_from_str() {
+            for scheme in schemes::all() {
+                let spec = scheme
+                    .iter()
+                    .map(|form| format!("{}/{}/{}", form.bit_count, form.prefix_len, form.prefix))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                assert_eq!(spec.parse::<EncodingScheme>().as_ref(), Ok(scheme));
+            }
+            assert_eq!("V358".parse::<EncodingScheme>(), Ok(schemes::V358.clone()));
+            assert_eq!(" SCHEME_f8 ".parse::<EncodingScheme>(), Ok(schemes::F8.clone()));
+            assert_eq!("3/1/1, 5/2/2 ,8/2/0".parse::<EncodingScheme>(), Ok(schemes::V358.clone()));
+            assert_eq!("4/0/0".parse::<EncodingScheme>(), Ok(schemes::F4.clone()));
+
+            assert_eq!("".parse::<EncodingScheme>(), Err(SchemeParseError::Empty));
+            assert_eq!("  ".parse::<EncodingScheme>(), Err(SchemeParseError::Empty));
+            assert_eq!("v359".parse::<EncodingScheme>(), Err(SchemeParseError::UnknownName("v359".to_string())));
+            assert_eq!("3/1".parse::<EncodingScheme>(), Err(SchemeParseError::MalformedForm(0)));
+            assert_eq!("3/1/1,5/2/2/0".parse::<EncodingScheme>(), Err(SchemeParseError::MalformedForm(1)));
+            assert_eq!("3/1/1,,8/2/0".parse::<EncodingScheme>(), Err(SchemeParseError::MalformedForm(1)));
+            assert_eq!("3/1/1,5/2/0x2".parse::<EncodingScheme>(), Err(SchemeParseError::MalformedForm(1)));
+            assert_eq!("300/0/0".parse::<EncodingScheme>(), Err(SchemeParseError::MalformedForm(0)));
+            assert_eq!("3/-1/0".parse::<EncodingScheme>(), Err(SchemeParseError::MalformedForm(0)));
+            assert_eq!(
+                "3/1/1,0/2/2".parse::<EncodingScheme>(),
+                Err(SchemeParseError::InvalidForm(1, FormValidationError::BadBitCount))
+            );
+            assert_eq!(
+                "3/1/2".parse::<EncodingScheme>(),
+                Err(SchemeParseError::InvalidForm(0, FormValidationError::InvalidPrefixData))
+            );
+            assert_eq!(
+                "4/1/1".parse::<EncodingScheme>(),
+                Err(SchemeParseError::InvalidScheme(SchemeValidationError::SingleFormWithPrefix))
+            );
+            assert_eq!(
+                "8/2/0,3/1/1".parse::<EncodingScheme>(),
+                Err(SchemeParseError::InvalidScheme(SchemeValidationError::BitCountNotSorted))
+            );
+        }
+
+        #[test]
+        fn encoding_scheme_common_forms() {
+            for scheme in schemes::all() {
+                assert_eq!(scheme.common_forms(scheme), scheme.forms());
+            }
+            assert_eq!(schemes::V358.common_forms(&schemes::V48), vec![]);
+            assert_eq!(schemes::F8.common_forms(&schemes::F4), vec![]);
+
+            let scheme = encoding_scheme(&[encoding_form(3, 1, 1), encoding_form(5, 2, 0b10), encoding_form(7, 2, 0b00)]);
+            assert_eq!(scheme.common_forms(&schemes::V358), vec![encoding_form(3, 1, 1), encoding_form(5, 2, 0b10)]);
+            assert_eq!(schemes::V358.common_forms(&scheme), vec![encoding_form(3, 1, 1), encoding_form(5, 2, 0b10)]);
+
+            // forms of the other scheme may go in a different order
+            let scheme1 = encoding_scheme(&[encoding_form(4, 3, 0b001), encoding_form(4, 1, 0b0)]);
+            let scheme2 = encoding_scheme(&[encoding_form(4, 1, 0b0), encoding_form(4, 3, 0b001)]);
+            assert_eq!(scheme1.common_forms(&scheme2), scheme1.forms());
+            assert_eq!(scheme2.common_forms(&scheme1), scheme2.forms());
+        }
+
+        #[test]
+        fn encoding_scheme_one_hop_labels() {
+            let labels = schemes::F8.one_hop_labels().map(|label| label.bits()).collect::<Vec<_>>();
+            assert_eq!(labels, (0x100..=0x1ff).collect::<Vec<u64>>());
+
+            let labels = schemes::V358.one_hop_labels().map(|label| label.bits()).collect::<Vec<_>>();
+            assert_eq!(labels.len(), 8 + 32 + 256);
+            assert_eq!(&labels[..3], &[0x11, 0x13, 0x15]);
+            assert_eq!(labels[8], 0x82);
+            assert_eq!(labels[8 + 32], 0x400);
+            assert_eq!(labels.last(), Some(&0x7fc));
+
+            for scheme in schemes::all() {
+                for label in scheme.one_hop_labels().step_by(7) {
+                    let form = scheme.form_for_director(label.bits()).expect("no form for label");
+                    assert_eq!(label.bit_length(), form.size_bits() as u32);
+                }
+            }
+
+            // lazy, so huge forms are fine as long as only a few labels are taken
+            let scheme = encoding_scheme(&[encoding_form(31, 0, 0)]);
+            assert_eq!(scheme.one_hop_labels().nth(5).map(|label| label.bits()), Some((1 << 31) | 5));
+        }
+
+        #[test]
+        fn encoding_scheme_describe() {
+            assert_eq!(schemes::F8.describe(), r#"[{ bitCount: 8, prefix: "", prefixLen: 0 }]"#);
+            assert_eq!(
+                schemes::V358.describe(),
+                r#"[{ bitCount: 3, prefix: "1", prefixLen: 1 }, { bitCount: 5, prefix: "10", prefixLen: 2 }, { bitCount: 8, prefix: "00", prefixLen: 2 }]"#
+            );
+            let scheme = encoding_scheme(&[encoding_form(4, 3, 0b001), encoding_form(8, 3, 0b110)]);
+            assert_eq!(
+                scheme.describe(),
+                r#"[{ bitCount: 4, prefix: "001", prefixLen: 3 }, { bitCount: 8, prefix: "110", prefixLen: 3 }]"#
+            );
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn encoding_form_serde() {
+            let form = encoding_form(4, 1, 1);
+            let json = serde_json::to_string(&form).expect("failed to serialize");
+            assert_eq!(json, r#"{"bitCount":4,"prefixLen":1,"prefix":1}"#);
+            assert_eq!(serde_json::from_str::<EncodingSchemeForm>(&json).expect("failed to deserialize"), form);
+
+            let forms = serde_json::from_str::<Vec<EncodingSchemeForm>>(r#"[{"bitCount":3,"prefixLen":1,"prefix":1},{"bitCount":7,"prefixLen":1,"prefix":0}]"#)
+                .expect("failed to deserialize");
+            assert_eq!(encoding_scheme(&forms), *schemes::V37);
+
+            // invalid forms are rejected
+            assert!(serde_json::from_str::<EncodingSchemeForm>(r#"{"bitCount":0,"prefixLen":0,"prefix":0}"#).is_err());
+            assert!(serde_json::from_str::<EncodingSchemeForm>(r#"{"bitCount":4,"prefixLen":1,"prefix":2}"#).is_err());
+            assert!(serde_json::from_str::<EncodingSchemeForm>(r#"{"bit_count":4,"prefix_len":1,"prefix":1}"#).is_err());
+        }
+
+        #[cfg(feature = "serde_json")]
+        #[test]
+        fn encoding_scheme_from_admin_json() {
+            use crate::AdminJsonSchemeError;
+
+            let json = |s: &str| serde_json::from_str::<serde_json::Value>(s).expect("bad test json");
+
+            let scheme = EncodingScheme::from_admin_json(&json(
+                r#"[{"bitCount":3,"prefixLen":1,"prefix":1},{"bitCount":5,"prefixLen":2,"prefix":2},{"bitCount":8,"prefixLen":2,"prefix":0}]"#,
+            ));
+            assert_eq!(scheme, Ok(schemes::V358.clone()));
+            assert_eq!(
+                EncodingScheme::from_admin_json(&json(r#"[{"bitCount":8,"prefixLen":0,"prefix":0}]"#)),
+                Ok(schemes::F8.clone())
+            );
+
+            let err = |s: &str| EncodingScheme::from_admin_json(&json(s)).expect_err("invalid scheme accepted");
+            assert_eq!(
+                err(r#"{"bitCount":8,"prefixLen":0,"prefix":0}"#),
+                AdminJsonSchemeError::Malformed("scheme is not an array")
+            );
+            assert_eq!(err(r#"[8]"#), AdminJsonSchemeError::Malformed("form is not an object"));
+            assert_eq!(err(r#"[{"prefixLen":0,"prefix":0}]"#), AdminJsonSchemeError::Malformed("bitCount"));
+            assert_eq!(err(r#"[{"bitCount":8,"prefixLen":0,"prefix":"0"}]"#), AdminJsonSchemeError::Malformed("prefix"));
+            assert_eq!(
+                err(r#"[{"bitCount":8,"prefixLen":-1,"prefix":0}]"#),
+                AdminJsonSchemeError::Malformed("prefixLen")
+            );
+
+            assert_eq!(
+                err(r#"[{"bitCount":0,"prefixLen":0,"prefix":0}]"#),
+                AdminJsonSchemeError::InvalidForm(FormValidationError::BadBitCount)
+            );
+            assert_eq!(
+                err(r#"[{"bitCount":256,"prefixLen":0,"prefix":0}]"#),
+                AdminJsonSchemeError::InvalidForm(FormValidationError::BadBitCount)
+            );
+            assert_eq!(
+                err(r#"[{"bitCount":4,"prefixLen":1,"prefix":2}]"#),
+                AdminJsonSchemeError::InvalidForm(FormValidationError::InvalidPrefixData)
+            );
+
+            assert_eq!(err(r#"[]"#), AdminJsonSchemeError::InvalidScheme(SchemeValidationError::InvalidFormsAmount));
+            assert_eq!(
+                err(r#"[{"bitCount":8,"prefixLen":1,"prefix":0},{"bitCount":4,"prefixLen":1,"prefix":1}]"#),
+                AdminJsonSchemeError::InvalidScheme(SchemeValidationError::BitCountNotSorted)
+            );
+        }
+
+        #[cfg(feature = "arbitrary")]
+        #[test]
+        fn encoding_scheme_arbitrary() {
+            use arbitrary::{Arbitrary, Unstructured};
+
+            let data = (0..4096_u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect::<Vec<_>>();
+            for start in 0..1024 {
+                let mut u = Unstructured::new(&data[start..]);
+                let scheme = EncodingScheme::arbitrary(&mut u).expect("failed to generate scheme");
+                assert_eq!(EncodingScheme::validate(&scheme), Ok(()));
+                assert_eq!(crate::deserialize_scheme(&scheme.serialize()), Ok(scheme));
+
+                let form = EncodingSchemeForm::arbitrary(&mut u).expect("failed to generate form");
+                assert_eq!(EncodingSchemeForm::try_new(form.bit_count, form.prefix_len, form.prefix), Ok(form));
+            }
+        }
+
         #[test]
         fn schemes() {
             assert_eq!(&**schemes::F8, &[encoding_form(8, 0, 0)]);
@@ -560,60 +2291,222 @@ mod encoding_scheme {
             assert_eq!(schemes::V358[0].bit_count, 3);
             assert_eq!(schemes::V358[2].bit_count, 8);
         }
+
+        #[test]
+        fn schemes_by_name() {
+            assert_eq!(schemes::scheme_by_name("f4"), Some(&*schemes::F4));
+            assert_eq!(schemes::scheme_by_name("F8"), Some(&*schemes::F8));
+            assert_eq!(schemes::scheme_by_name("v48"), Some(&*schemes::V48));
+            assert_eq!(schemes::scheme_by_name("SCHEME_v358"), Some(&*schemes::V358));
+            assert_eq!(schemes::scheme_by_name("v37"), Some(&*schemes::V37));
+            assert_eq!(schemes::scheme_by_name("v3x5x8"), None);
+            assert_eq!(schemes::scheme_by_name(""), None);
+        }
+
+        #[test]
+        fn schemes_serialization() {
+            // https://github.com/cjdelisle/cjdnsencode/blob/89216230daa82eb43689c6af48de3c6a138002f1/test.js
+            assert_eq!(serialize_scheme_hex(&schemes::F4), Ok("8000".to_string()));
+            assert_eq!(serialize_scheme_hex(&schemes::F8), Ok("0001".to_string()));
+            assert_eq!(serialize_scheme_hex(&schemes::V48), Ok("810c08".to_string()));
+            assert_eq!(serialize_scheme_hex(&schemes::V358), Ok("6114458100".to_string()));
+        }
+    }
+}
+
+/// Serde `with` module which (de)serializes `EncodingScheme` as a hex string of its serialized form,
+/// the way encoding schemes are transferred by cjdns admin RPC.
+///
+/// ```rust
+/// # use cjdns_core::{schemes, EncodingScheme};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Peer {
+///     #[serde(with = "cjdns_core::serde_hex")]
+///     encoding_scheme: EncodingScheme,
+/// }
+///
+/// let peer: Peer = serde_json::from_str(r#"{"encoding_scheme": "6114458100"}"#).expect("invalid peer");
+/// assert_eq!(peer.encoding_scheme, *schemes::V358);
+/// assert_eq!(serde_json::to_string(&peer).expect("failed to serialize"), r#"{"encoding_scheme":"6114458100"}"#);
+/// ```
+///
+/// Invalid hex string or data which doesn't deserialize into a valid scheme results in a serde error.
+#[cfg(all(feature = "serde", feature = "std"))]
+pub mod serde_hex {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::{deserialize_scheme_hex, serialize_scheme_hex, EncodingScheme};
+
+    /// Serializes `scheme` as a hex string.
+    pub fn serialize<S: Serializer>(scheme: &EncodingScheme, serializer: S) -> Result<S::Ok, S::Error> {
+        let scheme_hex = serialize_scheme_hex(scheme).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&scheme_hex)
+    }
+
+    /// Deserializes `EncodingScheme` from a hex string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<EncodingScheme, D::Error> {
+        let scheme_hex = String::deserialize(deserializer)?;
+        deserialize_scheme_hex(&scheme_hex).map_err(D::Error::custom)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use serde::{Deserialize, Serialize};
+
+        use crate::{schemes, EncodingScheme};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Peer {
+            #[serde(with = "crate::serde_hex")]
+            scheme: EncodingScheme,
+        }
+
+        #[test]
+        fn test_serde_hex() {
+            let peer = Peer { scheme: schemes::V358.clone() };
+            let json = serde_json::to_string(&peer).expect("failed to serialize");
+            assert_eq!(json, r#"{"scheme":"6114458100"}"#);
+            assert_eq!(serde_json::from_str::<Peer>(&json).expect("failed to deserialize"), peer);
+
+            assert!(serde_json::from_str::<Peer>(r#"{"scheme":"61144581zz"}"#).is_err());
+            assert!(serde_json::from_str::<Peer>(r#"{"scheme":"611445810"}"#).is_err());
+            assert!(serde_json::from_str::<Peer>(r#"{"scheme":"0000"}"#).is_err());
+            assert!(serde_json::from_str::<Peer>(r#"{"scheme":[8,0]}"#).is_err());
+        }
     }
 }
 
 mod errors {
+    use alloc::string::String;
+    #[cfg(feature = "std")]
     use thiserror::Error;
 
     /// Error returned when scheme validation fails
-    #[derive(Error, Debug, PartialEq, Eq)]
+    #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(Error))]
     pub enum SchemeValidationError {
         /// Invalid scheme forms length. Should be in 1..32 range.
-        #[error("Invalid encoding scheme: amount of encoding forms is not in range of (1..32)")]
+        #[cfg_attr(feature = "std", error("Invalid encoding scheme: amount of encoding forms is not in range of (1..32)"))]
         InvalidFormsAmount,
 
         /// Scheme with single form must not have non-empty prefix value
-        #[error("Invalid encoding scheme: single form has non-empty prefix")]
+        #[cfg_attr(feature = "std", error("Invalid encoding scheme: single form has non-empty prefix"))]
         SingleFormWithPrefix,
 
         /// Scheme with multiple forms must have non-empty prefix value
-        #[error("Invalid encoding scheme: multiple forms - prefix length is out of bounds (1..32)")]
+        #[cfg_attr(feature = "std", error("Invalid encoding scheme: multiple forms - prefix length is out of bounds (1..32)"))]
         MultiFormBadPrefix,
 
+        /// Form prefix value doesn't fit in `prefix_len` bits
+        #[cfg_attr(feature = "std", error("Invalid encoding scheme: form prefix doesn't fit in prefix_len bits"))]
+        PrefixTooLong,
+
         /// Multiple forms should have `bit_count` in ascending order
-        #[error("Invalid encoding scheme: multiple forms should have bit_count in ascending order")]
+        #[cfg_attr(feature = "std", error("Invalid encoding scheme: multiple forms should have bit_count in ascending order"))]
         BitCountNotSorted,
 
         /// Multiple forms must have unique prefixes
-        #[error("Invalid encoding scheme: multiple forms must have unique prefixes")]
+        #[cfg_attr(feature = "std", error("Invalid encoding scheme: multiple forms must have unique prefixes"))]
         DuplicatePrefix,
 
+        /// Prefix of a form matches lower bits of another form's prefix
+        #[cfg_attr(feature = "std", error("Invalid encoding scheme: form prefix matches lower bits of another form's prefix"))]
+        AmbiguousPrefixes,
+
         /// Encoding scheme cannot be represented in the usable space in a 64-bit label
-        #[error("Invalid encoding scheme: encoding scheme cannot be represented in the usable space in a 64-bit label")]
+        #[cfg_attr(
+            feature = "std",
+            error("Invalid encoding scheme: encoding scheme cannot be represented in the usable space in a 64-bit label")
+        )]
         TooBigForm,
+
+        /// Serialized scheme is longer than allowed
+        #[cfg_attr(feature = "std", error("Invalid encoding scheme: serialized encoding scheme is too long"))]
+        SerializedTooLong,
     }
 
-    #[derive(Error, Debug, PartialEq, Eq)]
+    #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(Error))]
     pub enum FormValidationError {
         /// Scheme bit count value out of valid range (which is 1..32)
-        #[error("Invalid encoding form: `bit_count` out of bounds (1..32)")]
+        #[cfg_attr(feature = "std", error("Invalid encoding form: `bit_count` out of bounds (1..32)"))]
         BadBitCount,
 
+        /// Prefix length value out of valid range (which is 0..32)
+        #[cfg_attr(feature = "std", error("Invalid encoding form: `prefix_len` out of bounds (0..32)"))]
+        BadPrefixLen,
+
         /// Encoded prefix length is insufficient for the provided prefix
-        #[error("Invalid encoding form: `prefix_len` is to little for provided `prefix`")]
+        #[cfg_attr(feature = "std", error("Invalid encoding form: `prefix_len` is to little for provided `prefix`"))]
         InvalidPrefixData,
+
+        /// Director doesn't fit in `bit_count` bits of the form
+        #[cfg_attr(feature = "std", error("Director is too big for the encoding form"))]
+        DirectorTooBig,
+    }
+
+    /// Error returned when parsing encoding scheme from cjdns admin API JSON fails
+    #[cfg(feature = "serde_json")]
+    #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(Error))]
+    pub enum AdminJsonSchemeError {
+        /// JSON doesn't have the expected structure, holds the name of a missing or bad field or a description of the problem
+        #[cfg_attr(feature = "std", error("Malformed encoding scheme JSON: {0}"))]
+        Malformed(&'static str),
+
+        /// One of the forms is invalid
+        #[cfg_attr(feature = "std", error("{0}"))]
+        InvalidForm(#[cfg_attr(feature = "std", source)] FormValidationError),
+
+        /// Forms are valid, but don't make a valid scheme
+        #[cfg_attr(feature = "std", error("{0}"))]
+        InvalidScheme(#[cfg_attr(feature = "std", source)] SchemeValidationError),
+    }
+
+    /// Error returned when parsing encoding scheme from a spec string fails
+    #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(Error))]
+    pub enum SchemeParseError {
+        /// Spec string is empty
+        #[cfg_attr(feature = "std", error("Encoding scheme spec string is empty"))]
+        Empty,
+
+        /// Spec string is neither a list of forms nor a name of a well-known scheme
+        #[cfg_attr(feature = "std", error("Unknown encoding scheme name: {0:?}"))]
+        UnknownName(String),
+
+        /// Form with the given (zero-based) index is not a `bit_count/prefix_len/prefix` triple of decimal numbers
+        #[cfg_attr(feature = "std", error("Malformed encoding form #{0}: expected `bit_count/prefix_len/prefix` decimal numbers"))]
+        MalformedForm(usize),
+
+        /// Form with the given (zero-based) index is invalid
+        #[cfg_attr(feature = "std", error("Invalid encoding form #{0}: {1}"))]
+        InvalidForm(usize, #[cfg_attr(feature = "std", source)] FormValidationError),
+
+        /// Forms are valid, but don't make a valid scheme
+        #[cfg_attr(feature = "std", error("{0}"))]
+        InvalidScheme(#[cfg_attr(feature = "std", source)] SchemeValidationError),
     }
 
     /// Error returned when encoding scheme for serialization/deserialization fails
-    #[derive(Error, Debug, PartialEq, Eq)]
+    #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(Error))]
     pub enum EncodingSerializationError {
+        /// Returned when serialized scheme is empty
+        #[cfg_attr(feature = "std", error("Serialized encoding scheme is empty"))]
+        EmptyInput,
+
         /// Returned when scheme serialization fails
-        #[error("Invalid serialized encoding scheme")]
+        #[cfg_attr(feature = "std", error("Invalid serialized encoding scheme"))]
         BadSerializedData,
 
         /// Returned when encoding form deserialization fails
-        #[error("Invalid encoding form")]
+        #[cfg_attr(feature = "std", error("Invalid encoding form"))]
         BadEncodingForm,
+
+        /// Returned when the output buffer can't hold serialized scheme
+        #[cfg_attr(feature = "std", error("Buffer is too small for serialized encoding scheme"))]
+        BufferTooSmall,
     }
 }
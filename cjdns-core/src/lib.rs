@@ -1,17 +1,33 @@
 //! Entities operated by CJDNS.
+//!
+//! With `std` feature disabled the crate is `no_std` (but requires `alloc`) and provides only encoding schemes and forms
+//! with their validation and serialization.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate lazy_static;
 
+#[cfg(feature = "std")]
 pub use self::encoding::schemes;
 pub use self::encoding::*;
+#[cfg(feature = "std")]
 pub use self::pathhop::*;
+#[cfg(feature = "std")]
 pub use self::routinglabel::*;
+#[cfg(feature = "std")]
 pub use self::strconv::*;
 
 mod encoding;
+#[cfg(feature = "std")]
 mod pathhop;
+#[cfg(feature = "std")]
 mod routinglabel;
+#[cfg(feature = "std")]
 mod strconv;
 
+#[cfg(feature = "std")]
 pub mod splice;
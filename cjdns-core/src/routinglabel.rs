@@ -5,6 +5,11 @@ use std::mem::size_of;
 use std::ops::{Add, BitAnd, BitOr, BitXor, Shl, Shr, Sub};
 use std::u64;
 
+use thiserror::Error;
+
+use crate::splice;
+use crate::{EncodingScheme, EncodingSchemeForm, LabelParseError};
+
 /// Routing label (a sequence of encoded **Directors**).
 ///
 /// For more information on labels please refer to
@@ -12,6 +17,38 @@ use std::u64;
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct RoutingLabel<L: LabelBits>(L);
 
+/// Routing label operation failure, returned by label manipulation routines of `splice` module.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum LabelError {
+    /// Label requires more bits than it is available in underlying `LabelBits`.
+    #[error("Label is too long")]
+    Overflow,
+
+    /// Array argument must contain at least 2 items.
+    #[error("Not enough arguments")]
+    NotEnoughArguments,
+
+    /// Invalid function argument.
+    #[error("Bad argument")]
+    BadArgument,
+
+    /// Unable to unsplice because provided destination doesn't route through given mid path.
+    #[error("Can't unsplice")]
+    CannotUnsplice,
+
+    /// Can't find proper encoding form in encoding scheme: label is not recognized as using the given scheme.
+    #[error("Can't detect form")]
+    NoMatchingForm,
+
+    /// Label can't be re-encoded with the given form.
+    #[error("Can't re-encode")]
+    CannotReencode,
+
+    /// Label string can't be parsed, for callers parsing labels and manipulating them under a single error type.
+    #[error("{0}")]
+    ParseError(#[source] LabelParseError),
+}
+
 /// A 64 bit routing label.
 ///
 /// 64 bit labels are used by default.
@@ -54,6 +91,9 @@ pub trait LabelBits:
 }
 
 impl<L: LabelBits> RoutingLabel<L> {
+    /// Label which is a self-reference, i.e. the zero-length route to this node: `0000.0000.0000.0001`.
+    pub const SELF_REFERENCE: Self = RoutingLabel(L::ONE);
+
     /// Create new non-zero routing label. Returns `None` if `bits` is zero.
     pub fn try_new(bits: L) -> Option<Self> {
         if bits != L::ZERO {
@@ -66,8 +106,13 @@ impl<L: LabelBits> RoutingLabel<L> {
     /// Create a new label which is a self-reference.
     /// Corresponds to director with value of `1`.
     pub fn self_reference() -> Self {
-        let bits = L::ONE;
-        RoutingLabel(bits)
+        Self::SELF_REFERENCE
+    }
+
+    /// Checks whether this label is a self-reference, see `SELF_REFERENCE`.
+    #[inline]
+    pub fn is_self_reference(&self) -> bool {
+        *self == Self::SELF_REFERENCE
     }
 
     /// Raw data of this routing label. Always non-zero.
@@ -78,6 +123,67 @@ impl<L: LabelBits> RoutingLabel<L> {
         bits
     }
 
+    /// Number of bits occupied by the encoded directors, i.e. the index of the highest set bit.
+    ///
+    /// The highest set bit of a label is the terminator (the self-route `1` left after all the directors
+    /// are consumed), it is not counted, so this is `0` for the self-reference label and e.g. `4` for `0000.0000.0000.0013`.
+    /// This is how much space the label takes when spliced on top of another one.
+    #[inline]
+    pub fn bit_length(&self) -> u32 {
+        self.bits().highest_set_bit().expect("zero label")
+    }
+
+    /// Prepends a hop to this label, making it the first hop of the path: shifts the label left by `form.size_bits()`
+    /// and puts `director` encoded with `form` into the freed least significant bits.
+    ///
+    /// This is the way a node adds itself to a path, unlike `splice::splice` which joins whole labels.
+    /// The director is encoded as is, with no special handling for `V358` scheme (see `splice::label_from_directors`).
+    ///
+    /// Returns `Err(LabelError::CannotReencode)` if `director` doesn't fit into `form` and `Err(LabelError::Overflow)`
+    /// if the resulting label is longer than `splice::splice` allows.
+    pub fn prepend(&self, director: u64, form: &EncodingSchemeForm) -> splice::Result<Self> {
+        let (bit_count, prefix_len, prefix) = form.params();
+        if director >> bit_count != 0 {
+            return Err(LabelError::CannotReencode);
+        }
+        if self.bit_length() + form.size_bits() as u32 > L::MAX_PAYLOAD_BITS - 1 {
+            return Err(LabelError::Overflow);
+        }
+
+        let bits = (self.bits() << bit_count as u32) | L::from(director as u32);
+        let bits = (bits << prefix_len as u32) | L::from(prefix);
+        Ok(RoutingLabel(bits))
+    }
+
+    /// Truncates the label to its first `bits` bits of the path: keeps the `bits` least significant bits
+    /// and puts the terminating `1` bit right above them, so the result is a valid label again.
+    ///
+    /// Labels not longer than `bits` (see `bit_length`) are returned as is.
+    /// Cutting the label in the middle of a director is not checked, see `mask_to_hops` for that.
+    pub fn mask_to_bits(&self, bits: u32) -> Self {
+        if bits >= self.bit_length() {
+            return *self;
+        }
+        let terminator = L::ONE << bits;
+        RoutingLabel((self.bits() & (terminator - L::ONE)) | terminator)
+    }
+
+    /// Truncates the label to its first `n` hops, i.e. the path up to depth `n`,
+    /// assuming every node along the path uses the same `scheme`.
+    ///
+    /// Hops are peeled with `splice::next_hop`, whose errors are returned as is. Labels having at most `n` hops
+    /// are returned unchanged and zero hops give the self-reference label.
+    pub fn mask_to_hops(&self, n: usize, scheme: &EncodingScheme) -> splice::Result<Self> {
+        let mut rest = *self;
+        for _ in 0..n {
+            if rest.is_self_reference() {
+                return Ok(*self);
+            }
+            rest = splice::next_hop(rest, scheme)?.1;
+        }
+        Ok(self.mask_to_bits(self.bit_length() - rest.bit_length()))
+    }
+
     /// Size in bytes of this routing label
     #[inline]
     pub fn size(&self) -> usize {
@@ -85,6 +191,17 @@ impl<L: LabelBits> RoutingLabel<L> {
     }
 }
 
+/// Default label is the self-reference `0000.0000.0000.0001`, not zero: a label is never zero, as the highest set bit
+/// terminates the path, so the empty path is a single `1` bit.
+///
+/// The self-reference is the neutral element of splicing: `splice::checked_splice` of it and any label gives that label,
+/// so it is the natural starting point when folding a list of hops into one label.
+impl<L: LabelBits> Default for RoutingLabel<L> {
+    fn default() -> Self {
+        Self::SELF_REFERENCE
+    }
+}
+
 impl LabelBits for u32 {
     const ZERO: Self = 0;
     const ONE: Self = 1;
@@ -151,10 +268,117 @@ mod tests {
         assert_eq!(<u128 as LabelBits>::highest_set_bit(&(1 << 100)), Some(100));
     }
 
+    #[test]
+    fn test_prepend() {
+        let l64 = |v: u64| RoutingLabel::try_new(v).expect("bad test label");
+        let form = |bit_count, prefix_len, prefix| EncodingSchemeForm::try_new(bit_count, prefix_len, prefix).expect("bad test form");
+
+        let label = RoutingLabel::<u64>::SELF_REFERENCE.prepend(0b001, &form(3, 1, 1));
+        assert_eq!(label, Ok(l64(0x13)));
+        assert_eq!(label.and_then(|label| label.prepend(0b010, &form(3, 1, 1))), Ok(l64(0x135)));
+        assert_eq!(l64(0x13).prepend(0x4a, &form(8, 2, 0)), Ok(l64(0x4d28)));
+        assert_eq!(l64(0x13).prepend(0, &form(8, 0, 0)), Ok(l64(0x1300)));
+        assert_eq!(
+            RoutingLabel::<u128>::SELF_REFERENCE.prepend(0x7fff_ffff, &form(31, 0, 0)),
+            Ok(RoutingLabel::try_new(0xffff_ffff).expect("bad test label"))
+        );
+
+        assert_eq!(l64(0x13).prepend(0b1000, &form(3, 1, 1)), Err(LabelError::CannotReencode));
+        assert_eq!(l64(0x13).prepend(u64::MAX, &form(31, 1, 1)), Err(LabelError::CannotReencode));
+
+        // u64 label payload is limited to 60 bits, i.e. the highest set bit may be at most 59
+        let long = l64(1 << 51);
+        assert_eq!(long.prepend(0xff, &form(8, 0, 0)), Ok(l64((1 << 59) | 0xff)));
+        assert_eq!(long.prepend(0xff, &form(8, 1, 0)), Err(LabelError::Overflow));
+        assert_eq!(l64(1 << 63).prepend(0, &form(1, 0, 0)), Err(LabelError::Overflow));
+        assert_eq!(l64(u64::MAX).prepend(0, &form(1, 0, 0)), Err(LabelError::Overflow));
+    }
+
+    #[test]
+    fn test_mask_to_bits() {
+        let l64 = |v: u64| RoutingLabel::try_new(v).expect("bad test label");
+        assert_eq!(l64(0x153).mask_to_bits(4), l64(0x13));
+        assert_eq!(l64(0x153).mask_to_bits(0), RoutingLabel::SELF_REFERENCE);
+        assert_eq!(l64(0x153).mask_to_bits(6), l64(0x53));
+        assert_eq!(l64(0x153).mask_to_bits(8), l64(0x153));
+        assert_eq!(l64(0x153).mask_to_bits(100), l64(0x153));
+        assert_eq!(l64(u64::MAX).mask_to_bits(62), l64(u64::MAX >> 1));
+        assert_eq!(RoutingLabel::<u64>::SELF_REFERENCE.mask_to_bits(0), RoutingLabel::SELF_REFERENCE);
+        assert_eq!(
+            RoutingLabel::<u128>::try_new(u128::MAX).expect("bad test label").mask_to_bits(64).bits(),
+            (1 << 64) | 0xffff_ffff_ffff_ffff
+        );
+    }
+
+    #[test]
+    fn test_mask_to_hops() {
+        let l64 = |v: u64| RoutingLabel::try_new(v).expect("bad test label");
+        let v358 = &*crate::schemes::V358;
+        let label = l64(0x0003_64b5_10e5);
+        let directors = splice::label_to_directors(label, v358).expect("bad test label");
+
+        assert_eq!(label.mask_to_hops(0, v358), Ok(RoutingLabel::SELF_REFERENCE));
+        for n in 1..=directors.len() {
+            let masked = label.mask_to_hops(n, v358).expect("failed to mask label");
+            assert_eq!(splice::label_to_directors(masked, v358), Ok(directors[..n].to_vec()));
+        }
+        assert_eq!(label.mask_to_hops(directors.len() + 1, v358), Ok(label));
+        assert_eq!(label.mask_to_hops(usize::MAX, v358), Ok(label));
+
+        assert_eq!(l64(0x153).mask_to_hops(1, v358), Ok(l64(0x13)));
+        assert_eq!(l64(0x153).mask_to_hops(2, v358), Ok(l64(0x153)));
+        assert_eq!(RoutingLabel::<u64>::SELF_REFERENCE.mask_to_hops(1, v358), Ok(RoutingLabel::SELF_REFERENCE));
+        // label ends in the middle of the second director
+        assert_eq!(l64(0x53).mask_to_hops(2, v358), Err(LabelError::BadArgument));
+    }
+
+    #[test]
+    fn test_bit_length() {
+        let l64 = |v: u64| RoutingLabel::try_new(v).expect("bad test label");
+        assert_eq!(RoutingLabel::<u64>::SELF_REFERENCE.bit_length(), 0);
+        assert_eq!(l64(0x13).bit_length(), 4);
+        assert_eq!(l64(0x153).bit_length(), 8);
+        assert_eq!(l64(0x0003_64b5_10e5).bit_length(), 33);
+        assert_eq!(l64(u64::MAX).bit_length(), 63);
+        assert_eq!(RoutingLabel::<u128>::try_new(u128::MAX).expect("bad test label").bit_length(), 127);
+    }
+
     #[test]
     fn test_self_reference() {
         assert_eq!("0000.0001", RoutingLabel::<u32>::self_reference().to_string());
         assert_eq!("0000.0000.0000.0001", RoutingLabel::<u64>::self_reference().to_string());
         assert_eq!("0000.0000.0000.0000.0000.0000.0000.0001", RoutingLabel::<u128>::self_reference().to_string());
+        assert_eq!(RoutingLabel::<u64>::self_reference(), RoutingLabel::<u64>::SELF_REFERENCE);
+        assert_eq!(RoutingLabel::<u64>::default(), RoutingLabel::<u64>::SELF_REFERENCE);
+        assert_eq!(RoutingLabel::<u128>::default().to_string(), "0000.0000.0000.0000.0000.0000.0000.0001");
+
+        assert!(RoutingLabel::<u32>::SELF_REFERENCE.is_self_reference());
+        assert!(RoutingLabel::<u64>::try_new(1).expect("bad test label").is_self_reference());
+        assert!(!RoutingLabel::<u64>::try_new(0x13).expect("bad test label").is_self_reference());
+        assert!(!RoutingLabel::<u128>::try_new(1 << 64 | 1).expect("bad test label").is_self_reference());
+    }
+
+    #[test]
+    fn test_label_error() {
+        use std::convert::TryFrom;
+
+        let parse_and_splice = |a: &str, b: &str| -> Result<RoutingLabel<u64>, LabelError> {
+            let a = RoutingLabel::try_from(a).map_err(LabelError::ParseError)?;
+            let b = RoutingLabel::try_from(b).map_err(LabelError::ParseError)?;
+            splice::splice(&[a, b])
+        };
+        assert_eq!(
+            parse_and_splice("0000.0000.0000.0015", "0000.0000.0000.0013"),
+            Ok(RoutingLabel::try_new(0x153).expect("bad test label"))
+        );
+        assert_eq!(
+            parse_and_splice("0000.0000.0000.0000", "0000.0000.0000.0013"),
+            Err(LabelError::ParseError(LabelParseError::ZeroRoutingLabel))
+        );
+        assert_eq!(
+            parse_and_splice("0000.0000.0000.0015", "0000.0000.0013"),
+            Err(LabelError::ParseError(LabelParseError::MalformedRoutingLabelStringValue))
+        );
+        assert_eq!(parse_and_splice("0000.0000.0000.0015", "8000.0000.0000.0013"), Err(LabelError::Overflow));
     }
 }
@@ -2,40 +2,12 @@
 
 #![deny(missing_docs)]
 
-use thiserror::Error;
+use std::convert::TryFrom;
 
-use crate::{schemes, EncodingScheme, EncodingSchemeForm, LabelBits, PathHop, RoutingLabel};
+use crate::{schemes, EncodingScheme, EncodingSchemeForm, LabelBits, LabelError, PathHop, RoutingLabel};
 
 /// Result type alias.
-pub type Result<T> = std::result::Result<T, SpliceError>;
-
-/// Routing label splice failure.
-#[derive(Error, Debug, PartialEq, Eq)]
-pub enum SpliceError {
-    /// Label requires more bits than it is available in underlying `LabelBits`.
-    #[error("Label is too long")]
-    LabelTooLong,
-
-    /// Array argument must contain at least 2 items.
-    #[error("Not enough arguments")]
-    NotEnoughArguments,
-
-    /// Invalid function argument.
-    #[error("Bad argument")]
-    BadArgument,
-
-    /// Unable to unsplice because provided destination doesn't route through given mid path.
-    #[error("Can't unsplice")]
-    CannotUnsplice,
-
-    /// Can't find proper encoding form in encoding scheme: label is not recognized as using the given scheme.
-    #[error("Can't detect form")]
-    CannotFindForm,
-
-    /// Label can't be re-encoded with the given form.
-    #[error("Can't re-encode")]
-    CannotReencode,
-}
+pub type Result<T> = std::result::Result<T, LabelError>;
 
 /// This function takes one or more `RoutingLabel`s and splices them to create a resulting label.
 ///
@@ -44,7 +16,7 @@ pub enum SpliceError {
 ///
 /// Remember that the arguments should be read right to left, the first hop is the furthest to the right in the splice function.
 /// If the result of the splicing is too long to fit in a label (`LabelBits<T>::MAX_PAYLOAD_BITS` bits)
-/// then it will return `Err(Error::LabelTooLong)`.
+/// then it will return `Err(Error::Overflow)`.
 ///
 /// ```rust
 /// # use cjdns_core::splice::splice;
@@ -62,27 +34,43 @@ pub enum SpliceError {
 /// See: [LabelSplicer_splice()](https://github.com/cjdelisle/cjdns/blob/cjdns-v20.2/switch/LabelSplicer.h#L36)
 pub fn splice<L: LabelBits>(labels: &[RoutingLabel<L>]) -> Result<RoutingLabel<L>> {
     if labels.len() < 2 {
-        return Err(SpliceError::NotEnoughArguments);
+        return Err(LabelError::NotEnoughArguments);
     }
 
-    let mut result_bits = labels[0].bits();
-    for addon in &labels[1..] {
-        let addon_bitlen = label_highest_set_bit(addon);
-        let result_hsb = result_bits.highest_set_bit().expect("zero"); // All labels are always non-zero, so highest_set_bit() always available
-        if result_hsb + addon_bitlen > L::MAX_PAYLOAD_BITS - 1 {
-            return Err(SpliceError::LabelTooLong);
-        }
+    let mut result = labels[0];
+    for &addon in &labels[1..] {
+        result = checked_splice(result, addon).ok_or(LabelError::Overflow)?;
+    }
+    Ok(result)
+}
 
-        result_bits = ((result_bits ^ L::ONE) << addon_bitlen) ^ addon.bits();
+/// Splices two labels, same as `splice(&[label, addon])`, but returns `None` if the result is too long,
+/// like `checked_add` and friends do on overflow. Useful on hot paths, where the error details are not needed.
+///
+/// ```rust
+/// # use cjdns_core::splice::checked_splice;
+/// # use cjdns_core::RoutingLabel;
+/// # use std::convert::TryFrom;
+/// # let l = |s: &str| RoutingLabel::<u64>::try_from(s).unwrap();
+/// assert_eq!(checked_splice(l("0000.0000.0000.0015"), l("0000.0000.0000.0013")), Some(l("0000.0000.0000.0153")));
+/// assert_eq!(checked_splice(l("0400.0000.0000.1111"), l("0000.0000.0000.0005")), None);
+/// ```
+#[inline]
+pub fn checked_splice<L: LabelBits>(label: RoutingLabel<L>, addon: RoutingLabel<L>) -> Option<RoutingLabel<L>> {
+    let addon_bitlen = label_highest_set_bit(&addon);
+    if label_highest_set_bit(&label) + addon_bitlen > L::MAX_PAYLOAD_BITS - 1 {
+        return None;
     }
 
-    RoutingLabel::try_new(result_bits).ok_or(()).map_err(|_| unreachable!("result_bits is zero"))
+    // `addon` has its highest bit set, so the result is never zero
+    let result_bits = ((label.bits() ^ L::ONE) << addon_bitlen) ^ addon.bits();
+    Some(RoutingLabel::try_new(result_bits).expect("result_bits is zero"))
 }
 
 /// Get the **encoding form** used for the first **director** of the `RoutingLabel`.
 /// It also returns index of found **form** in **scheme**.
 /// Recall an encoding **scheme** is one or more encoding **forms**.
-/// If the label is not recognized as using the given scheme then it'll return `Err(Error::CannotFindForm)`.
+/// If the label is not recognized as using the given scheme then it'll return `Err(Error::NoMatchingForm)`.
 ///
 /// ```rust
 /// # use cjdns_core::splice::get_encoding_form;
@@ -114,7 +102,7 @@ pub fn get_encoding_form<L: LabelBits>(label: RoutingLabel<L>, scheme: &Encoding
         }
     }
 
-    Err(SpliceError::CannotFindForm)
+    Err(LabelError::NoMatchingForm)
 }
 
 /// Extracts a director stripping the encoding.
@@ -146,7 +134,7 @@ fn test_director_bit_length() {
 /// Index of highest set bit in label's binary representation.
 #[inline]
 fn label_highest_set_bit<L: LabelBits>(label: &RoutingLabel<L>) -> u32 {
-    label.bits().highest_set_bit().expect("zero label")
+    label.bit_length()
 }
 
 #[test]
@@ -171,7 +159,7 @@ fn find_shortest_form<L: LabelBits>(dir: L, scheme: &EncodingScheme) -> Result<E
         .filter(|&form| (form.params().0 as u32) >= dir_bits)
         .min_by_key(|&form| form.params().0)
         .map(|&form| form)
-        .ok_or(SpliceError::CannotFindForm)
+        .ok_or(LabelError::NoMatchingForm)
 }
 
 /// Re-encode a `label` to the encoding form specified by `desired_form_num`
@@ -180,7 +168,7 @@ fn find_shortest_form<L: LabelBits>(dir: L, scheme: &EncodingScheme) -> Result<E
 /// This will re-encode a label to the **encoding form** specified by `desired_form_num`.
 /// This may return an error if the encoding form cannot
 /// be detected, you pass an invalid **desired_form_num** or if you try to re-encode the self route
-/// (`0001`). It will also return an error if re-encoding a label will make it too long (more than `Label::max_bit_size()`
+/// (`0001`), or if the director doesn't fit into the desired form. It will also return an error if re-encoding a label will make it too long (more than `Label::max_bit_size()`
 /// bits). If desired_form_num is `None` then it will re-encode the label
 /// into it's *cannonical* form, that is the smallest form which can hold that director.
 ///
@@ -210,13 +198,12 @@ pub fn re_encode<L: LabelBits>(label: RoutingLabel<L>, scheme: &EncodingScheme,
     let mut desired_form = if let Some(num) = desired_form_num {
         let num = num as usize;
         if num >= scheme.len() {
-            return Err(SpliceError::BadArgument);
+            return Err(LabelError::BadArgument);
         }
         scheme[num]
     } else {
         find_shortest_form(dir, scheme)?
     };
-    let (desired_bit_count, desired_prefix_len, desired_prefix) = desired_form.params();
 
     if *scheme == *schemes::V358 {
         // Special magic for SCHEME_358 legacy.
@@ -230,7 +217,7 @@ pub fn re_encode<L: LabelBits>(label: RoutingLabel<L>, scheme: &EncodingScheme,
 
         if is_358_zero_form(form) {
             if dir == L::ZERO {
-                return Err(SpliceError::CannotReencode);
+                return Err(LabelError::CannotReencode);
             }
             dir = dir - L::ONE;
         }
@@ -239,6 +226,14 @@ pub fn re_encode<L: LabelBits>(label: RoutingLabel<L>, scheme: &EncodingScheme,
         }
     }
 
+    // desired form may have been changed above
+    let (desired_bit_count, desired_prefix_len, desired_prefix) = desired_form.params();
+
+    // director must fit into the desired form
+    if director_bit_length(dir) > desired_bit_count as u32 {
+        return Err(LabelError::CannotReencode);
+    }
+
     // Construct result: [bits before extracted dir][padded dir][desired form prefix]
     let mut result_bits = {
         let (bit_count, prefix_len, _) = form.params();
@@ -252,7 +247,7 @@ pub fn re_encode<L: LabelBits>(label: RoutingLabel<L>, scheme: &EncodingScheme,
     };
     let used_bits = rest_bitlen + desired_bit_count as u32 + desired_prefix_len as u32;
     if used_bits > L::MAX_PAYLOAD_BITS {
-        return Err(SpliceError::LabelTooLong);
+        return Err(LabelError::Overflow);
     }
 
     result_bits = (result_bits << (desired_bit_count as u32)) | dir;
@@ -322,7 +317,7 @@ pub fn is_one_hop<L: LabelBits>(label: RoutingLabel<L>, encoding_scheme: &Encodi
 /// Notice the second to last hop in the `path` has been changed from 001b to 0092. This is a re-encoding to ensure that the `label` remains the right length as the reverse path for this hop is 00ee which is longer than 001b.
 pub fn build_label<L: LabelBits>(path_hops: &[PathHop<L>]) -> Result<(RoutingLabel<L>, Vec<RoutingLabel<L>>)> {
     if path_hops.len() < 2 {
-        return Err(SpliceError::NotEnoughArguments);
+        return Err(LabelError::NotEnoughArguments);
     }
 
     if let (Some(first_hop), Some(last_hop)) = (path_hops.first(), path_hops.last()) {
@@ -335,10 +330,10 @@ pub fn build_label<L: LabelBits>(path_hops: &[PathHop<L>]) -> Result<(RoutingLab
 }
 
 fn build_label_impl<L: LabelBits>(first_hop: &PathHop<L>, mid_hops: &[PathHop<L>], last_hop: &PathHop<L>) -> Result<(RoutingLabel<L>, Vec<RoutingLabel<L>>)> {
-    let first_hop_label_n = first_hop.label_n.ok_or(SpliceError::BadArgument)?; // must be Some
-    let _last_hop_label_p = last_hop.label_p.ok_or(SpliceError::BadArgument)?; // must be Some
-    first_hop.label_p.map_or(Ok(()), |_| Err(SpliceError::BadArgument))?; // must be None
-    last_hop.label_n.map_or(Ok(()), |_| Err(SpliceError::BadArgument))?; // must be None
+    let first_hop_label_n = first_hop.label_n.ok_or(LabelError::BadArgument)?; // must be Some
+    let _last_hop_label_p = last_hop.label_p.ok_or(LabelError::BadArgument)?; // must be Some
+    first_hop.label_p.map_or(Ok(()), |_| Err(LabelError::BadArgument))?; // must be None
+    last_hop.label_n.map_or(Ok(()), |_| Err(LabelError::BadArgument))?; // must be None
 
     let mut ret_path = Vec::with_capacity(mid_hops.len() + 1);
     ret_path.push(first_hop_label_n);
@@ -356,7 +351,7 @@ fn build_label_impl<L: LabelBits>(first_hop: &PathHop<L>, mid_hops: &[PathHop<L>
 
             ret_path.push(label_n);
         } else {
-            return Err(SpliceError::BadArgument);
+            return Err(LabelError::BadArgument);
         }
     }
 
@@ -371,6 +366,193 @@ fn build_label_impl<L: LabelBits>(first_hop: &PathHop<L>, mid_hops: &[PathHop<L>
     Ok((ret_label, ret_path))
 }
 
+/// Builds a label from a list of directors, one per hop, each one to be encoded with the scheme of the node at that hop.
+///
+/// Hops go in path order, the first one is the director at the node where the path starts.
+/// Each director is encoded with the shortest form of its scheme which can hold it,
+/// taking into account the special encoding of `V358` scheme, then the hops are spliced together.
+///
+/// ```rust
+/// # use cjdns_core::splice::label_from_directors;
+/// # use cjdns_core::{RoutingLabel, schemes};
+/// # use std::convert::TryFrom;
+/// # let l = |s: &str| RoutingLabel::<u64>::try_from(s).unwrap();
+/// let label = label_from_directors(&[(0, &*schemes::V358), (1, &*schemes::V358)]);
+/// assert_eq!(label, Ok(l("0000.0000.0000.0153")));
+/// ```
+///
+/// Returns `Err(Error::NotEnoughArguments)` if `hops` is empty, `Err(Error::NoMatchingForm)` if a director
+/// doesn't fit into any form of its scheme and `Err(Error::Overflow)` if the resulting label is too long.
+pub fn label_from_directors<L: LabelBits>(hops: &[(u64, &EncodingScheme)]) -> Result<RoutingLabel<L>> {
+    if hops.is_empty() {
+        return Err(LabelError::NotEnoughArguments);
+    }
+
+    let mut hop_labels = Vec::with_capacity(hops.len());
+    for &(director, scheme) in hops {
+        // the largest form can hold any encodable director,
+        // and unlike the smaller ones it has no special encoding in `V358` scheme
+        let form = scheme.iter().max_by_key(|form| form.params().0).expect("scheme has no forms");
+        let (bit_count, prefix_len, prefix) = form.params();
+        // forms are at most 31 bits wide, so any director which doesn't fit into `u32` doesn't fit into a form either
+        let director = u32::try_from(director).map_err(|_| LabelError::NoMatchingForm)?;
+        if director >> bit_count != 0 {
+            return Err(LabelError::NoMatchingForm);
+        }
+        if form.size_bits() as u32 > L::MAX_PAYLOAD_BITS {
+            return Err(LabelError::Overflow);
+        }
+
+        let bits = (L::ONE << (bit_count as u32)) | L::from(director);
+        let bits = (bits << (prefix_len as u32)) | L::from(prefix);
+        let label = RoutingLabel::try_new(bits).expect("label has self-route bit set");
+        hop_labels.push(re_encode(label, scheme, None)?);
+    }
+
+    if hop_labels.len() == 1 {
+        return Ok(hop_labels[0]);
+    }
+    hop_labels.reverse();
+    splice(&hop_labels)
+}
+
+/// Checks whether `director` of a node using `from` scheme can be represented in `to` scheme,
+/// i.e. whether some form of each scheme can hold it.
+///
+/// This is a precondition for carrying a director across a boundary between two nodes with different schemes,
+/// which saves a failed `re_encode` attempt. As in `label_from_directors`, `director` is the actual director value,
+/// the special encoding of `V358` scheme doesn't change which directors fit.
+///
+/// ```rust
+/// # use cjdns_core::splice::compatible_director;
+/// # use cjdns_core::schemes;
+/// assert!(compatible_director(0xff, &schemes::V358, &schemes::F8));
+/// assert!(!compatible_director(0xff, &schemes::V358, &schemes::F4));
+/// assert!(!compatible_director(0x100, &schemes::V48, &schemes::V358));
+/// ```
+pub fn compatible_director(director: u64, from: &EncodingScheme, to: &EncodingScheme) -> bool {
+    from.smallest_form_for(director).is_some() && to.smallest_form_for(director).is_some()
+}
+
+/// Splits a label into directors, one per hop, assuming every node along the path uses the same `scheme`.
+///
+/// This is the opposite of `label_from_directors`: directors go in path order, starting from the node
+/// where the path starts, and the special encoding of `V358` scheme is taken into account.
+/// The self-reference label has no directors.
+///
+/// ```rust
+/// # use cjdns_core::splice::label_to_directors;
+/// # use cjdns_core::{RoutingLabel, schemes};
+/// # use std::convert::TryFrom;
+/// # let l = |s: &str| RoutingLabel::<u64>::try_from(s).unwrap();
+/// assert_eq!(label_to_directors(l("0000.0000.0000.0153"), &schemes::V358), Ok(vec![0, 1]));
+/// assert_eq!(label_to_directors(l("0000.0000.0000.0001"), &schemes::V358), Ok(vec![]));
+/// ```
+///
+/// Returns `Err(Error::NoMatchingForm)` if some part of the label doesn't match any form of the scheme
+/// and `Err(Error::BadArgument)` if the label ends (with the terminating `1` bit) in the middle of a director.
+pub fn label_to_directors<L: LabelBits>(label: RoutingLabel<L>, scheme: &EncodingScheme) -> Result<Vec<L>> {
+    let mut directors = Vec::new();
+    let mut rest = label;
+    while !rest.is_self_reference() {
+        let (director, next_rest) = next_hop(rest, scheme)?;
+        directors.push(director);
+        rest = next_rest;
+    }
+    Ok(directors)
+}
+
+/// Splits the first hop off the `label`, as a switch does for every packet it forwards:
+/// returns the director of the first hop along with the rest of the label, which is the label for the next node.
+///
+/// The `scheme` is the one used by the node at the beginning of the path. The director is the same
+/// as the first one returned by `label_to_directors`, i.e. the special encoding of `V358` scheme is taken into account.
+///
+/// ```rust
+/// # use cjdns_core::splice::next_hop;
+/// # use cjdns_core::{RoutingLabel, schemes};
+/// # use std::convert::TryFrom;
+/// # let l = |s: &str| RoutingLabel::<u64>::try_from(s).unwrap();
+/// assert_eq!(next_hop(l("0000.0000.0000.0153"), &schemes::V358), Ok((0, l("0000.0000.0000.0015"))));
+/// assert_eq!(next_hop(l("0000.0000.0000.0015"), &schemes::V358), Ok((1, l("0000.0000.0000.0001"))));
+/// ```
+///
+/// Returns `Err(Error::NoMatchingForm)` if the label doesn't match any form of the scheme and `Err(Error::BadArgument)`
+/// if the label is the self-reference, which has no hops, or ends (with the terminating `1` bit) in the middle of the first director.
+pub fn next_hop<L: LabelBits>(label: RoutingLabel<L>, scheme: &EncodingScheme) -> Result<(L, RoutingLabel<L>)> {
+    if label.is_self_reference() {
+        return Err(LabelError::BadArgument);
+    }
+
+    let (form, form_num) = get_encoding_form(label, scheme)?;
+    let form_bits = form.size_bits() as u32;
+    if label.bit_length() < form_bits {
+        return Err(LabelError::BadArgument);
+    }
+
+    let mut director = get_director(label, form);
+    if *scheme == *schemes::V358 && form_num == 0 {
+        // 3-bit form of V358 holds `director + 1`
+        if director == L::ZERO {
+            return Err(LabelError::NoMatchingForm);
+        }
+        director = director - L::ONE;
+    }
+
+    let rest = RoutingLabel::try_new(label.bits() >> form_bits).expect("terminating bit is above the director");
+    Ok((director, rest))
+}
+
+/// Re-encodes every hop of the `label` with the smallest form of `scheme` which can hold its director,
+/// assuming every node along the path uses that scheme. Labels which differ only by the forms chosen
+/// for their hops become equal after normalization.
+///
+/// Hops are split off with `next_hop`, so the special encoding of `V358` scheme is taken into account:
+/// its 3-bit form holds `director + 1`, so it is the smallest form only for directors up to 6.
+/// Unlike `re_encode`, this never moves director 6 to the 5-bit form, so a label is left as is
+/// if all its hops already use their smallest forms.
+///
+/// ```rust
+/// # use cjdns_core::splice::normalize;
+/// # use cjdns_core::{RoutingLabel, schemes};
+/// # use std::convert::TryFrom;
+/// # let l = |s: &str| RoutingLabel::<u64>::try_from(s).unwrap();
+/// assert_eq!(normalize(l("0000.0000.0000.0404"), &schemes::V358), Ok(l("0000.0000.0000.0015")));
+/// assert_eq!(normalize(l("0000.0000.0000.0153"), &schemes::V358), Ok(l("0000.0000.0000.0153")));
+/// ```
+///
+/// Returns the same errors as `next_hop` for a label which doesn't use the `scheme`.
+pub fn normalize<L: LabelBits>(label: RoutingLabel<L>, scheme: &EncodingScheme) -> Result<RoutingLabel<L>> {
+    let is_358 = *scheme == *schemes::V358;
+    let mut hop_labels = Vec::new();
+    let mut rest = label;
+    while !rest.is_self_reference() {
+        let (director, next_rest) = next_hop(rest, scheme)?;
+        // 3-bit form of V358 holds `director + 1`
+        let stored_director = |form_num: usize| if is_358 && form_num == 0 { director + L::ONE } else { director };
+        let (form_num, form) = scheme
+            .iter()
+            .enumerate()
+            .find(|&(form_num, form)| director_bit_length(stored_director(form_num)) <= form.params().0 as u32)
+            .expect("director is decoded from a form of the scheme");
+
+        let (bit_count, prefix_len, prefix) = form.params();
+        let bits = (L::ONE << (bit_count as u32)) | stored_director(form_num);
+        let bits = (bits << (prefix_len as u32)) | L::from(prefix);
+        hop_labels.push(RoutingLabel::try_new(bits).expect("label has self-route bit set"));
+        rest = next_rest;
+    }
+
+    match hop_labels.len() {
+        0 => Ok(label),
+        1 => Ok(hop_labels[0]),
+        _ => {
+            hop_labels.reverse();
+            splice(&hop_labels)
+        }
+    }
+}
+
 /// This will return `true` if the node at the end of the route given by `mid_path` is a hop along the path given by `destination`.
 ///
 /// ```rust
@@ -409,7 +591,7 @@ pub fn routes_through<L: LabelBits>(destination: RoutingLabel<L>, mid_path: Rout
 /// See: [LabelSplicer_unsplice()](https://github.com/cjdelisle/cjdns/blob/77259a49e5bc7ca7bc6dca5bd423e02be563bdc5/switch/LabelSplicer.h#L31)
 pub fn unsplice<L: LabelBits>(destination: RoutingLabel<L>, mid_path: RoutingLabel<L>) -> Result<RoutingLabel<L>> {
     if !(routes_through(destination, mid_path)) {
-        return Err(SpliceError::CannotUnsplice);
+        return Err(LabelError::CannotUnsplice);
     }
 
     RoutingLabel::try_new(destination.bits() >> label_highest_set_bit(&mid_path))
@@ -417,6 +599,32 @@ pub fn unsplice<L: LabelBits>(destination: RoutingLabel<L>, mid_path: RoutingLab
         .map_err(|_| unreachable!("highest_set_bit() is broken"))
 }
 
+/// Strips the first hop off the `label`, as the node at the beginning of the path does when forwarding a packet.
+///
+/// The `encoding_scheme` argument is the one used by that node. The first hop director is recognized with `get_encoding_form()`
+/// and the label is shifted right by the size of its form, so the result is the label the next node along the path will see.
+/// Returns `Err(Error::NoMatchingForm)` if no form matches the label, and `Err(Error::CannotUnsplice)`
+/// if the label is too short to contain the whole first hop.
+///
+/// ```rust
+/// # use cjdns_core::splice::{splice, unsplice_first_hop};
+/// # use cjdns_core::{RoutingLabel, schemes};
+/// # use std::convert::TryFrom;
+/// # let l = |s: &str| RoutingLabel::<u64>::try_from(s).unwrap();
+/// assert_eq!(splice(&[l("0000.0000.0000.0015"), l("0000.0000.0000.0013")]), Ok(l("0000.0000.0000.0153")));
+/// assert_eq!(unsplice_first_hop(l("0000.0000.0000.0153"), &schemes::V358), Ok(l("0000.0000.0000.0015")));
+/// ```
+pub fn unsplice_first_hop<L: LabelBits>(label: RoutingLabel<L>, encoding_scheme: &EncodingScheme) -> Result<RoutingLabel<L>> {
+    let (form, _) = get_encoding_form(label, encoding_scheme)?;
+    let form_bits = form.size_bits() as u32;
+    if label_highest_set_bit(&label) < form_bits {
+        return Err(LabelError::CannotUnsplice);
+    }
+    RoutingLabel::try_new(label.bits() >> form_bits)
+        .ok_or(())
+        .map_err(|_| unreachable!("highest_set_bit() is broken"))
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
@@ -451,10 +659,20 @@ mod tests {
 
     #[test]
     fn test_splice() {
-        assert_eq!(splice::<u64>(&[]), Err(SpliceError::NotEnoughArguments));
-        assert_eq!(splice(&[l("0000.0000.0000.0015")]), Err(SpliceError::NotEnoughArguments));
+        assert_eq!(splice::<u64>(&[]), Err(LabelError::NotEnoughArguments));
+        assert_eq!(splice(&[l("0000.0000.0000.0015")]), Err(LabelError::NotEnoughArguments));
 
         assert_eq!(splice(&[l("0000.0000.0000.0015"), l("0000.0000.0000.0013")]), Ok(l("0000.0000.0000.0153")));
+        assert_eq!(splice(&[l("0000.0000.0000.0015"), l("0000.0000.0000.0153")]), Ok(l("0000.0000.0000.1553")));
+        assert_eq!(splice(&[l("0000.0000.0000.0015"), l("0000.0000.0000.0001")]), Ok(l("0000.0000.0000.0015")));
+        assert_eq!(
+            checked_splice(l("0000.0000.0000.0015"), l("0000.0000.0000.0013")),
+            Some(l("0000.0000.0000.0153"))
+        );
+        assert_eq!(
+            checked_splice(l("0000.0000.0000.0001"), l("0000.0000.0000.0013")),
+            Some(l("0000.0000.0000.0013"))
+        );
 
         assert_eq!(
             splice(&[l128("0000.0000.0000.0000.0000.0000.0000.0015"), l128("0000.0000.0000.0000.0000.0000.0000.0013")]),
@@ -492,10 +710,19 @@ mod tests {
             Ok(l128("0800.0000.0000.0000.0000.0000.0000.4445"))
         );
 
-        assert_eq!(splice(&[l("0400.0000.0000.1111"), l("0000.0000.0000.0005")]), Err(SpliceError::LabelTooLong));
+        assert_eq!(splice(&[l("0400.0000.0000.1111"), l("0000.0000.0000.0005")]), Err(LabelError::Overflow));
+        assert_eq!(
+            checked_splice(l("0200.0000.0000.1111"), l("0000.0000.0000.0005")),
+            Some(l("0800.0000.0000.4445"))
+        );
+        assert_eq!(checked_splice(l("0400.0000.0000.1111"), l("0000.0000.0000.0005")), None);
+        assert_eq!(
+            checked_splice(l("0400.0000.0000.1111"), l("0000.0000.0000.0001")),
+            Some(l("0400.0000.0000.1111"))
+        );
         assert_eq!(
             splice(&[l128("0400.0000.0000.0000.0000.0000.0000.1111"), l128("0000.0000.0000.0000.0000.0000.0000.0005")]),
-            Err(SpliceError::LabelTooLong)
+            Err(LabelError::Overflow)
         );
     }
 
@@ -543,6 +770,18 @@ mod tests {
 
         assert_eq!(re_encode(l("0040.0000.0000.0067"), &schemes::V48, Some(1)), Ok(l("0400.0000.0000.0606")));
         assert!(re_encode(l("0400.0000.0000.0067"), &schemes::V48, Some(1)).is_err());
+
+        // director doesn't fit into the desired form
+        assert_eq!(re_encode(l("0000.0000.0000.01fe"), &schemes::V48, Some(0)), Err(LabelError::CannotReencode));
+        assert_eq!(re_encode(l("0000.0000.0000.07fc"), &schemes::V358, Some(0)), Err(LabelError::CannotReencode));
+        assert_eq!(re_encode(l("0000.0000.0000.07fc"), &schemes::V358, Some(1)), Err(LabelError::CannotReencode));
+        assert_eq!(re_encode(l("0000.0000.0000.00c6"), &schemes::V358, Some(0)), Err(LabelError::CannotReencode));
+        assert_eq!(re_encode(l("0000.0000.0000.00c6"), &schemes::V358, Some(2)), Ok(l("0000.0000.0000.0444")));
+        // bits above the director are preserved
+        assert_eq!(re_encode(l("0000.0000.0153.0404"), &schemes::V358, Some(0)), Ok(l("0000.0000.0005.4c15")));
+        // director 7 can't go into the 3-bit form of V358, it is moved to the 5-bit one
+        assert_eq!(re_encode(l("0000.0000.0000.041c"), &schemes::V358, Some(0)), Ok(l("0000.0000.0000.009e")));
+        assert_eq!(re_encode(l("0000.0000.0000.041c"), &schemes::V358, None), Ok(l("0000.0000.0000.009e")));
     }
 
     #[test]
@@ -594,6 +833,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_reencode_358_vectors() {
+        // same director encoded with each of the 3 forms of V358, `None` if it doesn't fit;
+        // the 3-bit form holds `director + 1`, and director 7 goes into the 5-bit form instead.
+        // As in cjdns, the check for director 7 is done before the `+ 1` adjustment, so re-encoding `001f`
+        // to the 3-bit form (which it already uses) moves it to the 5-bit form.
+        let vectors = [
+            (0, Some("0013"), Some("0082"), "0400"),
+            (1, Some("0015"), Some("0086"), "0404"),
+            (6, Some("001f"), Some("009a"), "0418"),
+            (7, None, Some("009e"), "041c"),
+            (31, None, Some("00fe"), "047c"),
+            (32, None, None, "0480"),
+            (255, None, None, "07fc"),
+        ];
+        let lbl = |s: &str| l(&format!("0000.0000.0000.{}", s));
+        for &(director, form0, form1, form2) in &vectors {
+            let forms = [form0, form1, Some(form2)];
+            let canonical = forms.iter().flatten().next().map(|s| lbl(s)).expect("bad test");
+
+            for from in forms.iter().flatten() {
+                let quirk = director == 6 && form0 == Some(*from);
+                for (to_num, to) in forms.iter().enumerate() {
+                    let expected = match to {
+                        Some(_) if quirk && to_num == 0 => Ok(lbl("009a")),
+                        Some(to) => Ok(lbl(to)),
+                        None if director == 7 && to_num == 0 => Ok(canonical),
+                        None => Err(LabelError::CannotReencode),
+                    };
+                    assert_eq!(
+                        re_encode(lbl(from), &schemes::V358, Some(to_num as u8)),
+                        expected,
+                        "{} -> form {}",
+                        from,
+                        to_num
+                    );
+                }
+                let canonical = if quirk { lbl("009a") } else { canonical };
+                assert_eq!(re_encode(lbl(from), &schemes::V358, None), Ok(canonical), "{} -> canonical", from);
+            }
+        }
+    }
+
     #[test]
     fn test_reencode_358() {
         for i in 1..256 {
@@ -617,7 +899,13 @@ mod tests {
                     assert_eq!(re_encode(label1, &schemes::V358, None), Ok(label));
                 }
             }
-            //TODO what if form_num >= 2? Need some assert on it.
+            if form_num >= 1 {
+                // director is too big for the smaller forms
+                assert_eq!(re_encode(label, &schemes::V358, Some(0)), Err(LabelError::CannotReencode));
+            }
+            if form_num >= 2 {
+                assert_eq!(re_encode(label, &schemes::V358, Some(1)), Err(LabelError::CannotReencode));
+            }
         }
     }
 
@@ -683,24 +971,24 @@ mod tests {
             unsplice(l128("0000.0000.0000.0000.0000.0000.0000.0001"), l128("0000.0000.0000.0000.0000.0000.0000.0001")),
             Ok(l128("0000.0000.0000.0000.0000.0000.0000.0001"))
         );
-        assert_eq!(unsplice(l("0000.000b.0535.10e5"), l("0000.001b.0535.10e5")), Err(SpliceError::CannotUnsplice));
+        assert_eq!(unsplice(l("0000.000b.0535.10e5"), l("0000.001b.0535.10e5")), Err(LabelError::CannotUnsplice));
         assert_eq!(
             unsplice(l128("0000.0000.0000.0000.0000.000b.0535.10e5"), l128("0000.0000.0000.0000.0000.001b.0535.10e5")),
-            Err(SpliceError::CannotUnsplice)
+            Err(LabelError::CannotUnsplice)
         );
         assert_eq!(
             unsplice(l128("0000.0000.0000.0000.0000.0000.0000.0013"), l128("0000.0000.0000.0000.0000.0000.0000.0153")),
-            Err(SpliceError::CannotUnsplice)
+            Err(LabelError::CannotUnsplice)
         );
-        assert_eq!(unsplice(l("ffff.ffff.ffff.ffff"), l("0000.0000.0000.0002")), Err(SpliceError::CannotUnsplice));
+        assert_eq!(unsplice(l("ffff.ffff.ffff.ffff"), l("0000.0000.0000.0002")), Err(LabelError::CannotUnsplice));
         assert_eq!(
             unsplice(l128("ffff.ffff.ffff.ffff.ffff.ffff.ffff.ffff"), l128("0000.0000.0000.0000.0000.0000.0000.0002")),
-            Err(SpliceError::CannotUnsplice)
+            Err(LabelError::CannotUnsplice)
         );
-        assert_eq!(unsplice(l("0000.0000.0000.0101"), l("0000.0000.0000.0110")), Err(SpliceError::CannotUnsplice));
+        assert_eq!(unsplice(l("0000.0000.0000.0101"), l("0000.0000.0000.0110")), Err(LabelError::CannotUnsplice));
         assert_eq!(
             unsplice(l128("0000.0000.0000.0000.0000.0000.0000.0101"), l128("0000.0000.0000.0000.0000.0000.0000.0110")),
-            Err(SpliceError::CannotUnsplice)
+            Err(LabelError::CannotUnsplice)
         );
         let label64_array = vec![
             l("0000.0000.0000.0015"),
@@ -731,6 +1019,43 @@ mod tests {
         assert_eq!(test128_val, l128("0000.0000.0000.0000.0000.0000.0000.0001"));
     }
 
+    #[test]
+    fn test_unsplice_first_hop() {
+        assert_eq!(unsplice_first_hop(l("0000.0000.0000.0153"), &schemes::V358), Ok(l("0000.0000.0000.0015")));
+        assert_eq!(unsplice_first_hop(l("0000.0000.0000.0013"), &schemes::V358), Ok(l("0000.0000.0000.0001")));
+        assert_eq!(unsplice_first_hop(l("0000.001b.0535.10e5"), &schemes::V358), Ok(l("0000.0001.b053.510e")));
+        assert_eq!(unsplice_first_hop(l("0000.0003.64b5.10e5"), &schemes::V358), Ok(l("0000.0000.364b.510e")));
+        assert_eq!(unsplice_first_hop(l("0000.0000.0000.0404"), &schemes::V358), Ok(l("0000.0000.0000.0001")));
+        assert_eq!(unsplice_first_hop(l("0000.0000.0000.0153"), &schemes::F8), Ok(l("0000.0000.0000.0001")));
+        assert_eq!(
+            unsplice_first_hop(l128("0000.0000.0000.0000.0000.0000.0000.0153"), &schemes::V358),
+            Ok(l128("0000.0000.0000.0000.0000.0000.0000.0015"))
+        );
+
+        // every hop stripped in turn from the spliced label
+        let hops = [
+            l("0000.0000.0000.0015"),
+            l("0000.0000.0000.008e"),
+            l("0000.0000.0000.00a2"),
+            l("0000.0000.0000.001d"),
+            l("0000.0000.0000.0414"),
+            l("0000.0000.0000.001b"),
+        ];
+        let mut labels = hops;
+        labels.reverse();
+        let mut label = splice(&labels).expect("bad test data");
+        for hop in hops.iter() {
+            assert_eq!(is_one_hop(*hop, &schemes::V358), Ok(true));
+            label = unsplice_first_hop(label, &schemes::V358).expect("failed to unsplice");
+        }
+        assert_eq!(label, l("0000.0000.0000.0001"));
+
+        assert_eq!(unsplice_first_hop(l("0000.0000.0000.0003"), &schemes::V358), Err(LabelError::CannotUnsplice));
+        assert_eq!(unsplice_first_hop(l("0000.0000.0000.0001"), &schemes::F4), Err(LabelError::CannotUnsplice));
+        let scheme = encoding_scheme(&[encoding_form(4, 2, 0b01), encoding_form(8, 2, 0b00)]);
+        assert_eq!(unsplice_first_hop(l("0000.0000.0000.0012"), &scheme), Err(LabelError::NoMatchingForm));
+    }
+
     #[test]
     fn test_build_label() {
         assert_eq!(
@@ -757,7 +1082,7 @@ mod tests {
         );
         assert_eq!(
             build_label(&[PathHop::new(lopt("0000.0000.0000.0013"), lopt("0000.0000.0000.0000"), &schemes::V358,)]),
-            Err(SpliceError::NotEnoughArguments)
+            Err(LabelError::NotEnoughArguments)
         );
         assert_eq!(
             build_label(&[
@@ -771,28 +1096,28 @@ mod tests {
                 PathHop::new(lopt("0000.0000.0000.0000"), lopt("0000.0000.0000.0015"), &schemes::V358,),
                 PathHop::new(lopt("0000.0000.0000.0000"), lopt("0000.0000.0000.0000"), &schemes::V358,),
             ]),
-            Err(SpliceError::BadArgument)
+            Err(LabelError::BadArgument)
         );
         assert_eq!(
             build_label(&[
                 PathHop::new(lopt("0000.0000.0000.0000"), lopt("0000.0000.0000.0000"), &schemes::V358,),
                 PathHop::new(lopt("0000.0000.0000.0013"), lopt("0000.0000.0000.0000"), &schemes::V358,),
             ]),
-            Err(SpliceError::BadArgument)
+            Err(LabelError::BadArgument)
         );
         assert_eq!(
             build_label(&[
                 PathHop::new(lopt("0000.0000.0000.0001"), lopt("0000.0000.0000.0015"), &schemes::V358,),
                 PathHop::new(lopt("0000.0000.0000.0013"), lopt("0000.0000.0000.0000"), &schemes::V358,),
             ]),
-            Err(SpliceError::BadArgument)
+            Err(LabelError::BadArgument)
         );
         assert_eq!(
             build_label(&[
                 PathHop::new(lopt("0000.0000.0000.0000"), lopt("0000.0000.0000.0015"), &schemes::V358,),
                 PathHop::new(lopt("0000.0000.0000.0013"), lopt("0000.0000.0000.0001"), &schemes::V358,),
             ]),
-            Err(SpliceError::BadArgument)
+            Err(LabelError::BadArgument)
         );
         assert_eq!(
             build_label(&[
@@ -800,7 +1125,7 @@ mod tests {
                 PathHop::new(lopt("0000.0000.0000.0000"), lopt("0000.0000.0000.008e"), &schemes::V358,),
                 PathHop::new(lopt("0000.0000.0000.0013"), lopt("0000.0000.0000.0000"), &schemes::V358,),
             ]),
-            Err(SpliceError::BadArgument)
+            Err(LabelError::BadArgument)
         );
         assert_eq!(
             build_label(&[
@@ -808,7 +1133,7 @@ mod tests {
                 PathHop::new(lopt("0000.0000.0000.009e"), lopt("0000.0000.0000.0000"), &schemes::V358,),
                 PathHop::new(lopt("0000.0000.0000.0013"), lopt("0000.0000.0000.0000"), &schemes::V358,),
             ]),
-            Err(SpliceError::BadArgument)
+            Err(LabelError::BadArgument)
         );
         assert_eq!(
             build_label(&[
@@ -873,6 +1198,205 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_label_from_directors() {
+        let v358 = &*schemes::V358;
+        let v48 = &*schemes::V48;
+        let f8 = &*schemes::F8;
+
+        assert_eq!(label_from_directors(&[(0, v358), (1, v358)]), Ok(l("0000.0000.0000.0153")));
+        assert_eq!(label_from_directors(&[(0, v358)]), Ok(l("0000.0000.0000.0013")));
+        assert_eq!(label_from_directors(&[(6, v358)]), Ok(l("0000.0000.0000.001f")));
+        assert_eq!(label_from_directors(&[(7, v358)]), Ok(l("0000.0000.0000.009e")));
+        assert_eq!(label_from_directors(&[(0xff, v358)]), Ok(l("0000.0000.0000.07fc")));
+        assert_eq!(label_from_directors(&[(3, v48)]), Ok(l("0000.0000.0000.0027")));
+        assert_eq!(label_from_directors(&[(0x10, v48)]), Ok(l("0000.0000.0000.0220")));
+        assert_eq!(label_from_directors(&[(0x13, f8)]), Ok(l("0000.0000.0000.0113")));
+        assert_eq!(label_from_directors(&[(3, v48), (0x13, f8)]), Ok(l("0000.0000.0000.2267")));
+        assert_eq!(
+            label_from_directors(&[(3, v48), (0x13, f8)]).map(|label: RoutingLabel<u64>| label.bits()),
+            splice(&[l("0000.0000.0000.0113"), l("0000.0000.0000.0027")]).map(|label| label.bits())
+        );
+        assert_eq!(
+            label_from_directors(&[(0, v358), (1, v358)]),
+            Ok(l128("0000.0000.0000.0000.0000.0000.0000.0153"))
+        );
+
+        assert_eq!(label_from_directors::<u64>(&[]), Err(LabelError::NotEnoughArguments));
+        assert_eq!(label_from_directors::<u64>(&[(0x100, f8)]), Err(LabelError::NoMatchingForm));
+        assert_eq!(label_from_directors::<u64>(&[(1, v358), (0x100, v358)]), Err(LabelError::NoMatchingForm));
+        // directors are not truncated to `u32`
+        let scheme = encoding_scheme(&[encoding_form(31, 0, 0)]);
+        assert_eq!(label_from_directors::<u64>(&[(1 << 32, &scheme)]), Err(LabelError::NoMatchingForm));
+        assert_eq!(label_from_directors::<u128>(&[((1 << 32) | 0x13, f8)]), Err(LabelError::NoMatchingForm));
+        assert_eq!(label_from_directors::<u64>(&[(u64::MAX, v358)]), Err(LabelError::NoMatchingForm));
+        assert_eq!(label_from_directors::<u64>(&[(0xff, f8); 7]), Ok(l("01ff.ffff.ffff.ffff")));
+        assert_eq!(label_from_directors::<u64>(&[(0xff, f8); 8]), Err(LabelError::Overflow));
+    }
+
+    #[test]
+    fn test_label_to_directors() {
+        assert_eq!(label_to_directors(l("0000.0000.0000.0001"), &schemes::V358), Ok(vec![]));
+        assert_eq!(label_to_directors(l("0000.0000.0000.0013"), &schemes::V358), Ok(vec![0]));
+        assert_eq!(label_to_directors(l("0000.0000.0000.0153"), &schemes::V358), Ok(vec![0, 1]));
+        assert_eq!(label_to_directors(l("0000.0000.0000.009e"), &schemes::V358), Ok(vec![7]));
+        assert_eq!(label_to_directors(l("0000.0000.0000.041c"), &schemes::V358), Ok(vec![7]));
+        assert_eq!(label_to_directors(l("0000.0000.0000.4447"), &schemes::V48), Ok(vec![3, 0x11]));
+        assert_eq!(label_to_directors(l("0000.0000.0000.0113"), &schemes::F8), Ok(vec![0x13]));
+        assert_eq!(
+            label_to_directors(l128("0000.0000.0000.0000.0000.0000.0000.0153"), &schemes::V358),
+            Ok(vec![0, 1])
+        );
+
+        // round-trip, hops of the original labels are not necessarily in their shortest forms, so labels may differ
+        let v358 = &*schemes::V358;
+        for label in &["0000.0003.64b5.10e5", "0000.001b.0535.10e5", "0000.0000.0000.0404", "0000.0000.0000.07fc"] {
+            let directors = label_to_directors(l(label), v358).expect("bad test");
+            let hops = directors.iter().map(|&d| (d, v358)).collect::<Vec<_>>();
+            let label: RoutingLabel<u64> = label_from_directors(&hops).expect("bad test");
+            assert_eq!(label_to_directors(label, v358), Ok(directors));
+        }
+        let hops = [(3, v358), (200, v358), (0, v358), (7, v358), (31, v358)];
+        let label: RoutingLabel<u64> = label_from_directors(&hops).expect("bad test");
+        assert_eq!(label_to_directors(label, v358), Ok(vec![3, 200, 0, 7, 31]));
+
+        // forms not sorted by `bit_count`, labels are the same as with the sorted scheme
+        let forms = [encoding_form(8, 2, 0), encoding_form(4, 1, 1), encoding_form(6, 2, 2)];
+        let unsorted = EncodingScheme::try_new_with(&forms, crate::ValidationMode::Lenient).expect("bad test");
+        let sorted = unsorted.canonical();
+        let hops = [(3, &sorted), (0x1f, &sorted), (0xff, &sorted)];
+        let label: RoutingLabel<u64> = label_from_directors(&hops).expect("bad test");
+        assert_eq!(label, l("0000.0000.00ff.8fc7"));
+        assert_eq!(get_encoding_form(label, &unsorted), Ok((forms[1], 1)));
+        assert_eq!(label_to_directors(label, &unsorted), Ok(vec![3, 0x1f, 0xff]));
+        let hops = [(3, &unsorted), (0x1f, &unsorted), (0xff, &unsorted)];
+        assert_eq!(label_from_directors(&hops), Ok(label));
+
+        // no matching form
+        let scheme = encoding_scheme(&[encoding_form(5, 2, 2), encoding_form(8, 2, 0)]);
+        assert_eq!(label_to_directors(l("0000.0000.0000.1113"), &scheme), Err(LabelError::NoMatchingForm));
+        assert_eq!(label_to_directors(l("0000.0000.0000.0011"), &schemes::V358), Err(LabelError::NoMatchingForm));
+        // label ends in the middle of a director
+        assert_eq!(label_to_directors(l("0000.0000.0000.0002"), &schemes::V358), Err(LabelError::BadArgument));
+        assert_eq!(label_to_directors(l("0000.0000.0000.1133"), &schemes::F8), Err(LabelError::BadArgument));
+    }
+
+    #[test]
+    fn test_splice_fold() {
+        let fold = |hops: &[RoutingLabel<u64>]| hops.iter().try_fold(RoutingLabel::default(), |acc, &hop| checked_splice(acc, hop));
+
+        assert_eq!(fold(&[]), Some(RoutingLabel::SELF_REFERENCE));
+        assert_eq!(fold(&[l("0000.0000.0000.0013")]), Some(l("0000.0000.0000.0013")));
+        assert_eq!(fold(&[l("0000.0000.0000.0015"), l("0000.0000.0000.0013")]), Some(l("0000.0000.0000.0153")));
+        assert_eq!(
+            fold(&[l("0000.0000.0000.0015"), l("0000.0000.0000.0013")]),
+            splice(&[l("0000.0000.0000.0015"), l("0000.0000.0000.0013")]).ok()
+        );
+        assert_eq!(fold(&[l("0400.0000.0000.1111"), l("0000.0000.0000.0005")]), None);
+
+        // self-reference is the neutral element on both sides
+        for &label in &[l("0000.0000.0000.0013"), l("0000.0003.64b5.10e5"), l("0800.0000.0000.0000")] {
+            assert_eq!(checked_splice(RoutingLabel::default(), label), Some(label));
+            assert_eq!(checked_splice(label, RoutingLabel::default()), Some(label));
+        }
+    }
+
+    #[test]
+    fn test_compatible_director() {
+        assert!(compatible_director(0, &schemes::F4, &schemes::F4));
+        assert!(compatible_director(0xf, &schemes::F4, &schemes::V358));
+        assert!(compatible_director(0x7, &schemes::V358, &schemes::F4));
+        assert!(compatible_director(0xf, &schemes::V358, &schemes::F4));
+        assert!(!compatible_director(0x10, &schemes::V358, &schemes::F4));
+        assert!(compatible_director(0xff, &schemes::V48, &schemes::F8));
+        assert!(!compatible_director(0xff, &schemes::V48, &schemes::V37));
+
+        // director which couldn't come from `from` scheme
+        assert!(!compatible_director(0x10, &schemes::F4, &schemes::F8));
+        assert!(!compatible_director(u64::MAX, &schemes::F8, &schemes::F8));
+
+        for scheme in schemes::all() {
+            let max_director = scheme.max_directors();
+            assert!(compatible_director(max_director, scheme, scheme));
+            assert!(!compatible_director(max_director + 1, scheme, scheme));
+            for to in schemes::all() {
+                assert_eq!(compatible_director(max_director, scheme, to), to.max_directors() >= max_director);
+            }
+        }
+    }
+
+    #[test]
+    fn test_next_hop() {
+        assert_eq!(next_hop(l("0000.0000.0000.0013"), &schemes::V358), Ok((0, l("0000.0000.0000.0001"))));
+        assert_eq!(next_hop(l("0000.0000.0000.0153"), &schemes::V358), Ok((0, l("0000.0000.0000.0015"))));
+        assert_eq!(next_hop(l("0000.0000.0000.041c"), &schemes::V358), Ok((7, l("0000.0000.0000.0001"))));
+        assert_eq!(next_hop(l("0000.0000.0000.4447"), &schemes::V48), Ok((3, l("0000.0000.0000.0222"))));
+        assert_eq!(next_hop(l("0000.0003.64b5.10e5"), &schemes::V358), Ok((1, l("0000.0000.364b.510e"))));
+        assert_eq!(
+            next_hop(l128("0000.0000.0000.0000.0000.0000.0000.0153"), &schemes::V358),
+            Ok((0, l128("0000.0000.0000.0000.0000.0000.0000.0015")))
+        );
+
+        // walking the label hop by hop gives the same directors as `label_to_directors`
+        let label = l("0000.001b.0535.10e5");
+        let mut rest = label;
+        let mut directors = vec![];
+        while !rest.is_self_reference() {
+            let (director, next_rest) = next_hop(rest, &schemes::V358).expect("bad test");
+            assert_eq!(unsplice_first_hop(rest, &schemes::V358), Ok(next_rest));
+            directors.push(director);
+            rest = next_rest;
+        }
+        assert_eq!(label_to_directors(label, &schemes::V358), Ok(directors));
+
+        assert_eq!(next_hop(l("0000.0000.0000.0001"), &schemes::V358), Err(LabelError::BadArgument));
+        assert_eq!(next_hop(l("0000.0000.0000.0002"), &schemes::V358), Err(LabelError::BadArgument));
+        assert_eq!(next_hop(l("0000.0000.0000.0011"), &schemes::V358), Err(LabelError::NoMatchingForm));
+        let scheme = encoding_scheme(&[encoding_form(5, 2, 2), encoding_form(8, 2, 0)]);
+        assert_eq!(next_hop(l("0000.0000.0000.1113"), &scheme), Err(LabelError::NoMatchingForm));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let v358 = &*schemes::V358;
+        // director 0 in each form of V358
+        assert_eq!(normalize(l("0000.0000.0000.0013"), v358), Ok(l("0000.0000.0000.0013")));
+        assert_eq!(normalize(l("0000.0000.0000.0082"), v358), Ok(l("0000.0000.0000.0013")));
+        assert_eq!(normalize(l("0000.0000.0000.0400"), v358), Ok(l("0000.0000.0000.0013")));
+        // director 6 stays in the 3-bit form, unlike with `re_encode`
+        assert_eq!(normalize(l("0000.0000.0000.001f"), v358), Ok(l("0000.0000.0000.001f")));
+        assert_eq!(normalize(l("0000.0000.0000.009a"), v358), Ok(l("0000.0000.0000.001f")));
+        assert_eq!(normalize(l("0000.0000.0000.041c"), v358), Ok(l("0000.0000.0000.009e")));
+        assert_eq!(normalize(l("0000.0000.0000.07fc"), v358), Ok(l("0000.0000.0000.07fc")));
+        assert_eq!(normalize(l("0000.0000.0000.0001"), v358), Ok(l("0000.0000.0000.0001")));
+        assert_eq!(normalize(l("0000.0000.0000.0113"), &schemes::F8), Ok(l("0000.0000.0000.0113")));
+        assert_eq!(normalize(l("0000.0000.0000.0021"), &schemes::V48), Ok(l("0000.0000.0000.0021")));
+        assert_eq!(normalize(l("0000.0000.0000.0206"), &schemes::V48), Ok(l("0000.0000.0000.0027")));
+
+        // multiple hops
+        let hops = [(3, v358), (200, v358), (0, v358), (7, v358), (31, v358)];
+        let label: RoutingLabel<u64> = label_from_directors(&hops).expect("bad test");
+        assert_eq!(normalize(label, v358), Ok(label));
+        let long_label = splice(&[l("0000.0000.0000.0404"), l("0000.0000.0000.0400"), l("0000.0000.0000.0082")]).expect("bad test");
+        assert_eq!(normalize(long_label, v358), Ok(l("0000.0000.0000.1533")));
+        assert_eq!(
+            label_to_directors(normalize(long_label, v358).expect("bad test"), v358),
+            label_to_directors(long_label, v358)
+        );
+        let long_label128 = splice(&[l128("0000.0000.0000.0000.0000.0000.0000.0404"), l128("0000.0000.0000.0000.0000.0000.0000.0400")]).expect("bad test");
+        assert_eq!(normalize(long_label128, v358), Ok(l128("0000.0000.0000.0000.0000.0000.0000.0153")));
+
+        // normalized label is a fixed point
+        for label in &["0000.0003.64b5.10e5", "0000.001b.0535.10e5", "0000.0000.0000.0404", "0000.0000.0000.07fc"] {
+            let normalized = normalize(l(label), v358).expect("bad test");
+            assert_eq!(normalize(normalized, v358), Ok(normalized));
+            assert_eq!(label_to_directors(normalized, v358), label_to_directors(l(label), v358));
+        }
+
+        assert_eq!(normalize(l("0000.0000.0000.0002"), v358), Err(LabelError::BadArgument));
+        assert_eq!(normalize(l("0000.0000.0000.0011"), v358), Err(LabelError::NoMatchingForm));
+    }
+
     #[test]
     fn test_is_one_hop() {
         assert_eq!(is_one_hop(l("0000.0000.0000.0013"), &schemes::V358), Ok(true));
@@ -894,7 +1418,7 @@ mod tests {
         assert_eq!(is_one_hop(l("0000.0000.0000.0110"), &schemes::V48), Ok(false));
         assert_eq!(
             is_one_hop(l("0000.0000.0000.1113"), &encoding_scheme(&[encoding_form(5, 2, 2), encoding_form(8, 2, 0),])),
-            Err(SpliceError::CannotFindForm)
+            Err(LabelError::NoMatchingForm)
         );
     }
 }
@@ -2,11 +2,13 @@
 //!
 //! RoutingLabel supports default formatting `format!("{}", routing_label)` (in hex form)
 //! and binary formatting `format!("{:b}", routing_label)`.
+//! Labels are parsed from the same dot-separated hex form with either `RoutingLabel::try_from(s)` or `s.parse()`.
 
 #![deny(missing_docs)]
 
 use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
 
 use regex::Regex;
 use thiserror::Error;
@@ -15,7 +17,7 @@ use super::RoutingLabel;
 
 /// Label string parsing errors.
 #[derive(Error, Copy, Clone, PartialEq, Eq, Debug)]
-pub enum LabelError {
+pub enum LabelParseError {
     /// Routing label string form is not valid and can not be parsed.
     #[error("Malformed routing label string")]
     MalformedRoutingLabelStringValue,
@@ -164,7 +166,7 @@ impl fmt::Binary for RoutingLabel<u128> {
 }
 
 impl TryFrom<&str> for RoutingLabel<u32> {
-    type Error = LabelError;
+    type Error = LabelParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         lazy_static! {
@@ -177,15 +179,15 @@ impl TryFrom<&str> for RoutingLabel<u32> {
         }
 
         if let Some(c) = RE.captures(value) {
-            Self::try_new((capture2u32(&c, 1) << 16) | capture2u32(&c, 2)).ok_or(LabelError::ZeroRoutingLabel)
+            Self::try_new((capture2u32(&c, 1) << 16) | capture2u32(&c, 2)).ok_or(LabelParseError::ZeroRoutingLabel)
         } else {
-            Err(LabelError::MalformedRoutingLabelStringValue)
+            Err(LabelParseError::MalformedRoutingLabelStringValue)
         }
     }
 }
 
 impl TryFrom<&str> for RoutingLabel<u64> {
-    type Error = LabelError;
+    type Error = LabelParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         lazy_static! {
@@ -199,15 +201,15 @@ impl TryFrom<&str> for RoutingLabel<u64> {
 
         if let Some(c) = RE.captures(value) {
             Self::try_new((capture2u64(&c, 1) << 48) | (capture2u64(&c, 2) << 32) | (capture2u64(&c, 3) << 16) | capture2u64(&c, 4))
-                .ok_or(LabelError::ZeroRoutingLabel)
+                .ok_or(LabelParseError::ZeroRoutingLabel)
         } else {
-            Err(LabelError::MalformedRoutingLabelStringValue)
+            Err(LabelParseError::MalformedRoutingLabelStringValue)
         }
     }
 }
 
 impl TryFrom<&str> for RoutingLabel<u128> {
-    type Error = LabelError;
+    type Error = LabelParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         lazy_static! {
@@ -242,13 +244,56 @@ impl TryFrom<&str> for RoutingLabel<u128> {
                     | (capture2u128(&c, 7) << 16)
                     | capture2u128(&c, 8),
             )
-            .ok_or(LabelError::ZeroRoutingLabel)
+            .ok_or(LabelParseError::ZeroRoutingLabel)
         } else {
-            Err(LabelError::MalformedRoutingLabelStringValue)
+            Err(LabelParseError::MalformedRoutingLabelStringValue)
         }
     }
 }
 
+impl FromStr for RoutingLabel<u32> {
+    type Err = LabelParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl FromStr for RoutingLabel<u64> {
+    type Err = LabelParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl FromStr for RoutingLabel<u128> {
+    type Err = LabelParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl RoutingLabel<u64> {
+    /// Splits the label into four 16 bit groups, most significant first,
+    /// i.e. the `aaaa.bbbb.cccc.dddd` string form is `[0xaaaa, 0xbbbb, 0xcccc, 0xdddd]`.
+    ///
+    /// Writing the groups as big-endian words gives the label wire layout.
+    pub fn to_groups(&self) -> [u16; 4] {
+        let bits = self.bits();
+        [(bits >> 48) as u16, (bits >> 32) as u16, (bits >> 16) as u16, bits as u16]
+    }
+
+    /// Builds the label from four 16 bit groups, most significant first, the opposite of `to_groups`.
+    ///
+    /// Returns `Err(LabelParseError::ZeroRoutingLabel)` if all the groups are zero.
+    pub fn from_groups(groups: [u16; 4]) -> Result<Self, LabelParseError> {
+        let bits = groups.iter().fold(0_u64, |bits, &group| (bits << 16) | group as u64);
+        RoutingLabel::try_new(bits).ok_or(LabelParseError::ZeroRoutingLabel)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate rand;
@@ -260,7 +305,7 @@ mod tests {
 
     use crate::RoutingLabel;
 
-    use super::LabelError;
+    use super::LabelParseError;
 
     fn l64(v: u64) -> RoutingLabel<u64> {
         RoutingLabel::try_new(v).expect("bad test data")
@@ -290,11 +335,11 @@ mod tests {
 
     #[test]
     fn label_from_string() {
-        assert_eq!(RoutingLabel::<u64>::try_from("0000.0000.0000.0000"), Err(LabelError::ZeroRoutingLabel));
+        assert_eq!(RoutingLabel::<u64>::try_from("0000.0000.0000.0000"), Err(LabelParseError::ZeroRoutingLabel));
         assert_eq!(RoutingLabel::<u64>::try_from("0000.0000.0000.0001"), Ok(l64(1)));
         assert_eq!(
             RoutingLabel::<u128>::try_from("0000.0000.0000.0000.0000.0000.0000.0000"),
-            Err(LabelError::ZeroRoutingLabel)
+            Err(LabelParseError::ZeroRoutingLabel)
         );
         assert_eq!(RoutingLabel::<u128>::try_from("0000.0000.0000.0000.0000.0000.0000.0001"), Ok(l128(1)));
         assert_eq!(RoutingLabel::<u64>::try_from("0000.0003.64b5.10e5"), Ok(l64(14574489829)));
@@ -315,6 +360,53 @@ mod tests {
         assert!(RoutingLabel::<u64>::try_from("").is_err());
     }
 
+    #[test]
+    fn label_parse() {
+        assert_eq!(
+            "0000.0015".parse::<RoutingLabel<u32>>(),
+            Ok(RoutingLabel::try_new(0x15).expect("bad test data"))
+        );
+        assert_eq!("0000.0000.0000.0013".parse::<RoutingLabel<u64>>(), Ok(l64(0x13)));
+        assert_eq!("0000.0003.64B5.10E5".parse::<RoutingLabel<u64>>(), Ok(l64(14574489829)));
+        assert_eq!("0000.0000.0000.0000.0000.0003.64b5.10e5".parse::<RoutingLabel<u128>>(), Ok(l128(14574489829)));
+
+        assert_eq!("0000.0000.0000.0000".parse::<RoutingLabel<u64>>(), Err(LabelParseError::ZeroRoutingLabel));
+        assert_eq!(
+            "0000.0000.0013".parse::<RoutingLabel<u64>>(),
+            Err(LabelParseError::MalformedRoutingLabelStringValue)
+        );
+        assert_eq!(
+            "0000.0000.0000.0000.0013".parse::<RoutingLabel<u64>>(),
+            Err(LabelParseError::MalformedRoutingLabelStringValue)
+        );
+        assert_eq!(
+            "0000.0000.0000.001g".parse::<RoutingLabel<u64>>(),
+            Err(LabelParseError::MalformedRoutingLabelStringValue)
+        );
+        assert_eq!(
+            "0000.0000.0000.0013".parse::<RoutingLabel<u32>>(),
+            Err(LabelParseError::MalformedRoutingLabelStringValue)
+        );
+    }
+
+    #[test]
+    fn label_groups() {
+        assert_eq!(l64(0x0003_64b5_10e5).to_groups(), [0x0000, 0x0003, 0x64b5, 0x10e5]);
+        assert_eq!(RoutingLabel::<u64>::from_groups([0x0000, 0x0003, 0x64b5, 0x10e5]), Ok(l64(0x0003_64b5_10e5)));
+        assert_eq!(RoutingLabel::<u64>::from_groups([0xffff; 4]), Ok(l64(u64::MAX)));
+        assert_eq!(RoutingLabel::<u64>::from_groups([0, 0, 0, 1]), Ok(RoutingLabel::<u64>::SELF_REFERENCE));
+        assert_eq!(RoutingLabel::<u64>::from_groups([0; 4]), Err(LabelParseError::ZeroRoutingLabel));
+
+        let mut rng = SmallRng::seed_from_u64(4914925427922294426u64);
+        for _ in 0..1000 {
+            let label = l64(rng.next_u64());
+            let groups = label.to_groups();
+            assert_eq!(RoutingLabel::<u64>::from_groups(groups), Ok(label));
+            let groups_str = groups.iter().map(|group| format!("{:04x}", group)).collect::<Vec<_>>().join(".");
+            assert_eq!(groups_str, label.to_string());
+        }
+    }
+
     #[test]
     fn l64_string_io() {
         let mut rng = SmallRng::seed_from_u64(4914925427922294426u64);
@@ -0,0 +1,156 @@
+//! CJDNS node address
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use cjdns_core::RoutingLabel;
+
+use crate::{
+    CJDNS_IP6,
+    CJDNSPublicKey,
+    errors::{KeyCreationError, Result},
+};
+
+/// CJDNS node address, as found in cjdns admin API output and peer lines.
+///
+/// Full string form of the address is `<ip6> v<version>.<label>.<public key>`, for example
+/// `fc32:6a5d:e235:7057:e990:6398:5d7a:aa58 v20.0000.0000.0000.0013.xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k`.
+/// Only the public key is mandatory, any other component can be omitted, e.g. `<ip6> <public key>` or `<label>.<public key>`.
+///
+/// The ip6 is not checked to be the one derived from the public key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CJDNSAddress {
+    /// Node ip6
+    pub ip6: Option<CJDNS_IP6>,
+
+    /// Protocol version of the node
+    pub version: Option<u32>,
+
+    /// Routing label (path) to the node
+    pub label: Option<RoutingLabel<u64>>,
+
+    /// Node public key
+    pub key: CJDNSPublicKey,
+}
+
+impl TryFrom<&str> for CJDNSAddress {
+    type Error = KeyCreationError;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let mut parts = value.split_whitespace().collect::<Vec<_>>();
+        let path = parts.pop().ok_or(KeyCreationError::BadString)?;
+        let ip6 = match parts.as_slice() {
+            [] => None,
+            [ip6] => Some(CJDNS_IP6::try_from(*ip6)?),
+            _ => return Err(KeyCreationError::BadString),
+        };
+
+        // path is `[v<version>.][<label>.]<public key>`, where public key itself ends with `.k`
+        let mut path_parts = path.split('.').collect::<Vec<_>>();
+        if path_parts.len() < 2 {
+            return Err(KeyCreationError::BadString);
+        }
+        let key_parts = path_parts.split_off(path_parts.len() - 2);
+        let key = CJDNSPublicKey::try_from(key_parts.join(".").as_str())?;
+
+        let version = match path_parts.first() {
+            Some(part) if part.starts_with('v') => {
+                let version = part[1..].parse::<u32>().or(Err(KeyCreationError::BadString))?;
+                path_parts.remove(0);
+                Some(version)
+            }
+            _ => None,
+        };
+
+        let label = match path_parts.len() {
+            0 => None,
+            4 => Some(RoutingLabel::<u64>::try_from(path_parts.join(".").as_str()).or(Err(KeyCreationError::BadString))?),
+            _ => return Err(KeyCreationError::BadString),
+        };
+
+        Ok(CJDNSAddress { ip6, version, label, key })
+    }
+}
+
+impl FromStr for CJDNSAddress {
+    type Err = KeyCreationError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::try_from(s)
+    }
+}
+
+impl std::fmt::Display for CJDNSAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(ip6) = &self.ip6 {
+            write!(f, "{} ", ip6)?;
+        }
+        if let Some(version) = self.version {
+            write!(f, "v{}.", version)?;
+        }
+        if let Some(label) = self.label {
+            write!(f, "{}.", label)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k";
+
+    fn address_r(s: &str) -> Result<CJDNSAddress> {
+        s.parse()
+    }
+
+    fn address(ip6: Option<&'static str>, version: Option<u32>, label: Option<&'static str>) -> CJDNSAddress {
+        CJDNSAddress {
+            ip6: ip6.map(|s| CJDNS_IP6::try_from(s).expect("bad test ipv6")),
+            version,
+            label: label.map(|s| RoutingLabel::try_from(s).expect("bad test label")),
+            key: CJDNSPublicKey::try_from(KEY).expect("bad test public key"),
+        }
+    }
+
+    #[test]
+    fn test_address_from_string() {
+        let full = "fc32:6a5d:e235:7057:e990:6398:5d7a:aa58 v20.0000.0000.0000.0013.xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k";
+        let full_address = address(Some("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58"), Some(20), Some("0000.0000.0000.0013"));
+        assert_eq!(address_r(full), Ok(full_address.clone()));
+        assert_eq!(full_address.to_string(), full);
+
+        let valid_cases = vec![
+            (KEY.to_string(), address(None, None, None)),
+            (format!("0000.0000.0000.0013.{}", KEY), address(None, None, Some("0000.0000.0000.0013"))),
+            (format!("v19.{}", KEY), address(None, Some(19), None)),
+            (format!("v21.0000.0003.64b5.10e5.{}", KEY), address(None, Some(21), Some("0000.0003.64b5.10e5"))),
+            (format!("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58 {}", KEY), address(Some("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58"), None, None)),
+            (
+                format!("  fc32:6a5d:e235:7057:e990:6398:5d7a:aa58\t0000.0000.0000.0013.{} ", KEY),
+                address(Some("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58"), None, Some("0000.0000.0000.0013")),
+            ),
+        ];
+        for (s, expected) in valid_cases {
+            assert_eq!(address_r(&s), Ok(expected));
+        }
+
+        let invalid_cases = vec![
+            "".to_string(),
+            "fc32:6a5d:e235:7057:e990:6398:5d7a:aa58".to_string(),
+            format!("0000.0000.0000.0013.{}.", KEY),
+            format!("0000.0000.0013.{}", KEY),
+            format!("0000.0000.0000.0000.{}", KEY),
+            format!("0000.0000.0000.001g.{}", KEY),
+            format!("vx.{}", KEY),
+            format!("0000.0000.0000.0013.v20.{}", KEY),
+            format!("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58 fc32:6a5d:e235:7057:e990:6398:5d7a:aa58 {}", KEY),
+            format!("ac32:6a5d:e235:7057:e990:6398:5d7a:aa58 {}", KEY),
+            format!("v20.0000.0000.0000.0013.{}", &KEY[1..]),
+        ];
+        for s in invalid_cases {
+            assert!(address_r(&s).is_err(), "{}", s);
+        }
+    }
+}
@@ -30,6 +30,34 @@ pub struct CJDNSKeys {
     pub ip6: CJDNS_IP6,
 }
 
+impl CJDNSKeys {
+    /// Generates a fresh random key pair with a valid ip6, same as `CJDNSKeysApi::key_pair`.
+    ///
+    /// Initializes `CJDNSKeysApi` on each call, so prefer using `CJDNSKeysApi` directly to generate many key pairs.
+    ///
+    /// # Panics
+    /// Panics if `CJDNSKeysApi` initialization fails.
+    pub fn gen() -> CJDNSKeys {
+        CJDNSKeysApi::new().expect("thread-safe initialization failed").key_pair()
+    }
+
+    /// Generates a fresh random key pair with ip6 starting with `hex_prefix`, same as `CJDNSKeysApi::key_pair_with_prefix`.
+    ///
+    /// # Panics
+    /// Panics if `CJDNSKeysApi` initialization fails.
+    pub fn gen_with_prefix(hex_prefix: &str, max_attempts: u64) -> Option<CJDNSKeys> {
+        CJDNSKeysApi::new().expect("thread-safe initialization failed").key_pair_with_prefix(hex_prefix, max_attempts)
+    }
+
+    /// Generates a fresh random key pair with ip6 satisfying `predicate`, same as `CJDNSKeysApi::key_pair_matching`.
+    ///
+    /// # Panics
+    /// Panics if `CJDNSKeysApi` initialization fails.
+    pub fn gen_matching(predicate: impl Fn(&CJDNS_IP6) -> bool, max_attempts: u64) -> Option<CJDNSKeys> {
+        CJDNSKeysApi::new().expect("thread-safe initialization failed").key_pair_matching(predicate, max_attempts)
+    }
+}
+
 impl CJDNSKeysApi {
     /// Initialization function, which guarantees on success that it will be safe to call methods
     /// which use "randomize" logic (i.e. `key_pair`, `gen_private_key`).
@@ -56,15 +84,44 @@ impl CJDNSKeysApi {
     ///
     /// `CJDNSKeys` doc states presence of a contract between ip6 and public key. The contract is met within the method.
     pub fn key_pair(&self) -> CJDNSKeys {
-        loop {
+        self.key_pair_matching(|_| true, u64::MAX).expect("no valid key pair within u64::MAX attempts")
+    }
+
+    /// Generates valid key "pair" with ip6 starting with `hex_prefix` (a "vanity" address).
+    ///
+    /// `hex_prefix` is matched against the beginning of the ip6 address hex digits, nibble by nibble,
+    /// so it must start with the mandatory `fc` and may end in the middle of a byte, e.g. `fcd` or `fcde:1`.
+    /// Colons are ignored and the case doesn't matter. Returns `None` if `hex_prefix` is not a valid address prefix
+    /// or if no matching key pair was found within `max_attempts` generated private keys, see `key_pair_matching`.
+    ///
+    /// About 256 private keys are tried on average to get an ip6 starting with `fc`, and every hex digit after `fc`
+    /// multiplies the expected amount of attempts by 16: `fcd` takes 4096 attempts on average, `fcde` - 65536 and so on.
+    pub fn key_pair_with_prefix(&self, hex_prefix: &str, max_attempts: u64) -> Option<CJDNSKeys> {
+        let hex_prefix = hex_prefix.replace(':', "").to_ascii_lowercase();
+        let is_valid_prefix = hex_prefix.starts_with("fc") && hex_prefix.len() <= 2 * CJDNS_IP6::SIZE && hex_prefix.chars().all(|c| c.is_ascii_hexdigit());
+        if !is_valid_prefix {
+            return None;
+        }
+
+        self.key_pair_matching(|ip6| hex::encode(ip6.raw()).starts_with(&hex_prefix), max_attempts)
+    }
+
+    /// Generates valid key "pair" with ip6 satisfying `predicate`, e.g. to avoid some byte patterns in the address.
+    ///
+    /// Each attempt generates one private key, which is a match if its ip6 is valid, i.e. starts with `fc`,
+    /// and satisfies `predicate`, so `predicate` only sees valid ip6 addresses. About 1 in 256 private keys gives a valid ip6.
+    /// Returns `None` if no matching key pair was found within `max_attempts` attempts.
+    pub fn key_pair_matching(&self, predicate: impl Fn(&CJDNS_IP6) -> bool, max_attempts: u64) -> Option<CJDNSKeys> {
+        for _ in 0..max_attempts {
             let private_key = self.gen_private_key();
             let public_key = CJDNSPublicKey::from(&private_key);
-            let ip6_candidate = CJDNS_IP6::try_from(&public_key);
-
-            if let Ok(ip6) = ip6_candidate {
-                return CJDNSKeys { private_key, public_key, ip6 };
+            if let Ok(ip6) = CJDNS_IP6::try_from(&public_key) {
+                if predicate(&ip6) {
+                    return Some(CJDNSKeys { private_key, public_key, ip6 });
+                }
             }
         }
+        None
     }
 
     /// Safely generates private key.
@@ -91,6 +148,63 @@ mod tests {
         CJDNS_IP6::try_from(s).expect("bad test ipv6")
     }
 
+    #[test]
+    fn test_key_pair_with_prefix() {
+        let keys_api = CJDNSKeysApi::new().expect("keys api init failed");
+
+        let keys = keys_api.key_pair_with_prefix("fc", 100_000).expect("no key pair found");
+        assert_eq!(CJDNS_IP6::try_from(&keys.public_key), Ok(keys.ip6.clone()));
+        assert_eq!(keys.public_key, CJDNSPublicKey::from(&keys.private_key));
+
+        let keys = keys_api.key_pair_with_prefix("FC5", 1_000_000).expect("no key pair found");
+        assert!(keys.ip6.to_string().starts_with("fc5"));
+
+        // invalid prefixes
+        assert_eq!(keys_api.key_pair_with_prefix("", 10), None);
+        assert_eq!(keys_api.key_pair_with_prefix("f", 10), None);
+        assert_eq!(keys_api.key_pair_with_prefix("fd", 10), None);
+        assert_eq!(keys_api.key_pair_with_prefix("5d", 10), None);
+        assert_eq!(keys_api.key_pair_with_prefix("fcxx", 10), None);
+        assert_eq!(keys_api.key_pair_with_prefix("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58:0", 10), None);
+
+        // no attempts
+        assert_eq!(keys_api.key_pair_with_prefix("fc", 0), None);
+    }
+
+    #[test]
+    fn test_key_pair_matching() {
+        let keys_api = CJDNSKeysApi::new().expect("keys api init failed");
+
+        let keys = keys_api.key_pair_matching(|ip6| ip6.raw()[15] == 0, 10_000_000).expect("no key pair found");
+        assert_eq!(keys.ip6.raw()[15], 0);
+        assert_eq!(CJDNS_IP6::try_from(&keys.public_key), Ok(keys.ip6.clone()));
+        assert_eq!(keys.public_key, CJDNSPublicKey::from(&keys.private_key));
+
+        let keys = keys_api.key_pair_matching(|ip6| !ip6.to_string().contains('0'), 10_000_000).expect("no key pair found");
+        assert!(!keys.ip6.to_string().contains('0'));
+
+        assert_eq!(keys_api.key_pair_matching(|_| true, 0), None);
+        assert_eq!(keys_api.key_pair_matching(|ip6| ip6.raw()[0] != 0xfc, 10), None);
+        // a single attempt is a single private key, which rarely gives a valid ip6
+        let found = (0..100).filter(|_| keys_api.key_pair_matching(|_| true, 1).is_some()).count();
+        assert!(found < 50);
+
+        let keys = CJDNSKeys::gen_matching(|ip6| ip6.raw()[15] == 0, 10_000_000).expect("no key pair found");
+        assert_eq!(keys.ip6.raw()[15], 0);
+        let keys = CJDNSKeys::gen_with_prefix("fc5", 1_000_000).expect("no key pair found");
+        assert!(keys.ip6.to_string().starts_with("fc5"));
+        assert_eq!(CJDNSKeys::gen_with_prefix("fd", 10), None);
+    }
+
+    #[test]
+    fn test_gen() {
+        let keys = CJDNSKeys::gen();
+        assert_eq!(keys.ip6.raw()[0], 0xfc);
+        assert_eq!(CJDNS_IP6::try_from(&keys.public_key), Ok(keys.ip6.clone()));
+        assert_eq!(keys.public_key, CJDNSPublicKey::from(&keys.private_key));
+        assert_ne!(CJDNSKeys::gen(), keys);
+    }
+
     #[test]
     fn test_base() {
         let key_pair = CJDNSKeys {
@@ -8,11 +8,22 @@ pub enum KeyCreationError {
     #[error("Wrong string format")]
     BadString,
 
+    #[error("Wrong string length: expected {expected} characters, got {got}")]
+    BadStringLength { expected: usize, got: usize },
+
+    #[error("Invalid character {character:?} at position {position}")]
+    BadCharacter { character: char, position: usize },
+
+    /// Shared secret computed with the public key is all zeroes, which is the case for some malicious (low order) public keys,
+    /// see `CJDNSPrivateKey::shared_secret`.
+    #[error("Public key is a low order point, shared secret is all zeroes")]
+    LowOrderPublicKey,
+
     #[error("Resulting IP6 address must start with 0xFC byte")]
     ResultingIp6OutOfValidRange,
 
-    #[error("Byte array must be exactly 16 bytes long")]
-    InvalidLength,
+    #[error("Invalid length of key bytes: expected {expected}, got {got}")]
+    InvalidLength { expected: usize, got: usize },
 }
 
 pub type Result<T> = std::result::Result<T, KeyCreationError>;
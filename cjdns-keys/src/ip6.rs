@@ -1,22 +1,25 @@
 //! CJDNS IP6
 
 use std::convert::TryFrom;
+use std::net::Ipv6Addr;
 use std::ops::Deref;
+use std::str::FromStr;
 
 use regex::Regex;
 
-use cjdns_crypto::hash::sha512;
-
 use crate::{
     CJDNSPublicKey,
     errors::{KeyCreationError, Result},
-    utils::{debug_fmt, slice_to_array16, vec_to_array16},
+    utils::{check_str, debug_fmt, slice_to_array16, vec_to_array16},
 };
 
 lazy_static! {
     static ref IP6_RE: Regex = Regex::new("^fc[0-9a-f]{2}:(?:[0-9a-f]{4}:){6}[0-9a-f]{4}$").expect("bad regexp");
 }
 
+// Full form of the address, eight groups of four hex digits separated by colons.
+const IP6_STRING_LEN: usize = 39;
+
 /// CJDNS IP6 type
 #[allow(non_camel_case_types)]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -32,12 +35,16 @@ impl CJDNS_IP6 {
     const FIRST_BYTE: u8 = 0xFC;
 }
 
+/// Derives node ip6 from its public key: the address is the first 16 bytes of the key's double SHA-512 hash,
+/// see `CJDNSPublicKey::address_hash`.
+///
+/// Returns `KeyCreationError::ResultingIp6OutOfValidRange` if the address doesn't start with `0xFC`. This never happens
+/// for keys generated by cjdns, as it keeps generating keys until one gives a valid address, see `CJDNSKeysApi::key_pair`.
 impl TryFrom<&CJDNSPublicKey> for CJDNS_IP6 {
     type Error = KeyCreationError;
 
     fn try_from(value: &CJDNSPublicKey) -> Result<Self> {
-        let pub_key_double_hash = sha512::hash(&sha512::hash(&value).0);
-        Self::try_from(&pub_key_double_hash[..Self::SIZE])
+        Self::try_from(&value.address_hash()[..Self::SIZE])
     }
 }
 
@@ -45,29 +52,77 @@ impl TryFrom<&[u8]> for CJDNS_IP6 {
     type Error = KeyCreationError;
 
     fn try_from(bytes: &[u8]) -> Result<Self> {
-        if bytes.len() != Self::SIZE {
-            return Err(KeyCreationError::InvalidLength);
-        }
-        if bytes[0] == Self::FIRST_BYTE || bytes == [0; Self::SIZE] {
-            return Ok(CJDNS_IP6 { k: slice_to_array16(bytes) });
+        let k = slice_to_array16(bytes)?;
+        if k[0] == Self::FIRST_BYTE || k == [0; Self::SIZE] {
+            return Ok(CJDNS_IP6 { k });
         }
         Err(KeyCreationError::ResultingIp6OutOfValidRange)
     }
 }
 
+impl TryFrom<[u8; 16]> for CJDNS_IP6 {
+    type Error = KeyCreationError;
+
+    fn try_from(bytes: [u8; 16]) -> Result<Self> {
+        Self::try_from(&bytes[..])
+    }
+}
+
+impl TryFrom<Ipv6Addr> for CJDNS_IP6 {
+    type Error = KeyCreationError;
+
+    fn try_from(addr: Ipv6Addr) -> Result<Self> {
+        // unlike raw bytes, unspecified address `::` is not accepted
+        if !Self::is_cjdns_ip6(&addr) {
+            return Err(KeyCreationError::ResultingIp6OutOfValidRange);
+        }
+        Ok(CJDNS_IP6 { k: addr.octets() })
+    }
+}
+
 impl TryFrom<&str> for CJDNS_IP6 {
     type Error = KeyCreationError;
 
     fn try_from(value: &str) -> Result<Self> {
         if IP6_RE.is_match(value) {
             let ip6_joined = value.split(":").collect::<String>();
-            let ip6_bytes = hex::decode(ip6_joined).expect("invalid hex string");
-            return Ok(CJDNS_IP6 { k: vec_to_array16(ip6_bytes) });
+            let ip6_bytes = hex::decode(ip6_joined).or(Err(KeyCreationError::BadString))?;
+            return Ok(CJDNS_IP6 { k: vec_to_array16(ip6_bytes)? });
         }
+        check_str(value, IP6_STRING_LEN, |c| c == ':' || c.is_ascii_digit() || ('a'..='f').contains(&c))?;
         Err(KeyCreationError::BadString)
     }
 }
 
+/// Lenient parsing: unlike `TryFrom<&str>`, which only accepts full lowercase form used by cjdns,
+/// this accepts any textual IPv6 representation, e.g. compressed `fc00::1` or uppercase.
+impl FromStr for CJDNS_IP6 {
+    type Err = KeyCreationError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let addr = s.parse::<Ipv6Addr>().or(Err(KeyCreationError::BadString))?;
+        Self::try_from(addr)
+    }
+}
+
+impl From<&CJDNS_IP6> for Ipv6Addr {
+    fn from(ip6: &CJDNS_IP6) -> Self {
+        Ipv6Addr::from(ip6.k)
+    }
+}
+
+impl PartialEq<Ipv6Addr> for CJDNS_IP6 {
+    fn eq(&self, other: &Ipv6Addr) -> bool {
+        self.k == other.octets()
+    }
+}
+
+impl PartialEq<CJDNS_IP6> for Ipv6Addr {
+    fn eq(&self, other: &CJDNS_IP6) -> bool {
+        *other == *self
+    }
+}
+
 impl Deref for CJDNS_IP6 {
     type Target = [u8];
 
@@ -76,8 +131,15 @@ impl Deref for CJDNS_IP6 {
     }
 }
 
+/// Formats the address in full form, as cjdns does, e.g. `fc00:0000:0000:0000:0000:0000:0000:0001`.
+///
+/// Alternate format `{:#}` gives RFC 5952 compressed form, the same as `Ipv6Addr` does, e.g. `fc00::1`,
+/// see `to_compressed_string`. Both forms are parsed back by `FromStr`.
 impl std::fmt::Display for CJDNS_IP6 {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if f.alternate() {
+            return std::fmt::Display::fmt(&Ipv6Addr::from(self), f);
+        }
         let mut ip6_string = hex::encode(self.k);
         // putting : after every 4th symbol
         for i in 1usize..8 {
@@ -95,6 +157,13 @@ impl std::fmt::Debug for CJDNS_IP6 {
 }
 
 impl CJDNS_IP6 {
+    /// Checks whether `addr` is structurally a valid cjdns address, i.e. belongs to `fc00::/8` network.
+    ///
+    /// It doesn't mean there is a known public key for such address.
+    pub fn is_cjdns_ip6(addr: &Ipv6Addr) -> bool {
+        addr.octets()[0] == Self::FIRST_BYTE
+    }
+
     pub fn is_zero(&self) -> bool {
         self.k == [0; 16]
     }
@@ -102,6 +171,16 @@ impl CJDNS_IP6 {
     pub fn raw(&self) -> &[u8; Self::SIZE] {
         &self.k
     }
+
+    /// Returns the address bytes, same as `Ipv6Addr::octets`.
+    pub fn octets(&self) -> [u8; Self::SIZE] {
+        self.k
+    }
+
+    /// Formats the address in RFC 5952 compressed form, e.g. `fc00::1`, same as `format!("{:#}", ip6)`.
+    pub fn to_compressed_string(&self) -> String {
+        format!("{:#}", self)
+    }
 }
 
 #[cfg(test)]
@@ -135,6 +214,28 @@ mod tests {
         for err_res in invalid_ips {
             assert!(err_res.is_err());
         }
+
+        assert_eq!(ipv6_r("fc32:6a5d7057:e990:6398:5d7a:aa58"), Err(KeyCreationError::BadStringLength { expected: 39, got: 33 }));
+        assert_eq!(ipv6_r("FC32:6a5d:e235:7057:e990:6398:5D7a:Aa58"), Err(KeyCreationError::BadCharacter { character: 'F', position: 0 }));
+        assert_eq!(ipv6_r("fc32:6a5de:235:7057:e990:6398:5d7a:aa58"), Err(KeyCreationError::BadString));
+        assert_eq!(ipv6_r("ac32:6a5d:e235:7057:e990:6398:5d7a:aa58"), Err(KeyCreationError::BadString));
+    }
+
+    #[test]
+    fn test_ip6_from_pub_key() {
+        let vectors = [
+            ("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k", "fc32:6a5d:e235:7057:e990:6398:5d7a:aa58"),
+            ("qgkjd0stfvk9r3j28s4gh8rgslbgx2r5xgxzxkgm5vdxqwn8xsu0.k", "fcf5:c1ec:be67:9ad5:1f6c:f31b:5d74:37b0"),
+            ("3fdqgz2vtqb0wx02hhvx3wjmjqktyt567fcuvj3m72vw5u6ubu70.k", "fc92:8136:dc1f:e6e0:4ef6:a6dd:7187:b85f"),
+        ];
+        for &(key, ip6) in &vectors {
+            let key = CJDNSPublicKey::try_from(key).expect("bad test public key");
+            assert_eq!(CJDNS_IP6::try_from(&key), Ok(ipv6(ip6)));
+        }
+
+        // double hash of zero key is `6d78a62a...`
+        let zero_key = CJDNSPublicKey::from([0; 32]);
+        assert_eq!(CJDNS_IP6::try_from(&zero_key), Err(KeyCreationError::ResultingIp6OutOfValidRange));
     }
 
     #[test]
@@ -156,11 +257,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ip6_from_std() {
+        let addr = "fc32:6a5d:e235:7057:e990:6398:5d7a:aa58".parse::<Ipv6Addr>().expect("bad test ipv6");
+        assert!(CJDNS_IP6::is_cjdns_ip6(&addr));
+        assert_eq!(CJDNS_IP6::try_from(addr), Ok(ipv6("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58")));
+
+        let addr = "fc00::1".parse::<Ipv6Addr>().expect("bad test ipv6");
+        assert!(CJDNS_IP6::is_cjdns_ip6(&addr));
+        assert_eq!(CJDNS_IP6::try_from(addr), Ok(ipv6("fc00:0000:0000:0000:0000:0000:0000:0001")));
+
+        for s in &["fd32:6a5d:e235:7057:e990:6398:5d7a:aa58", "2001:db8::1", "::1", "::"] {
+            let addr = s.parse::<Ipv6Addr>().expect("bad test ipv6");
+            assert!(!CJDNS_IP6::is_cjdns_ip6(&addr));
+            assert_eq!(CJDNS_IP6::try_from(addr), Err(KeyCreationError::ResultingIp6OutOfValidRange));
+        }
+    }
+
+    #[test]
+    fn test_ip6_eq_std() {
+        let ip6 = ipv6("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58");
+        let addr = "fc32:6a5d:e235:7057:e990:6398:5d7a:aa58".parse::<Ipv6Addr>().expect("bad test ipv6");
+        assert_eq!(ip6, addr);
+        assert_eq!(addr, ip6);
+        assert_eq!(Ipv6Addr::from(&ip6), ip6);
+
+        let ip6 = ipv6("fc00:0000:0000:0000:0000:0000:0000:0001");
+        let addr = "fc00::1".parse::<Ipv6Addr>().expect("bad test ipv6");
+        assert_eq!(ip6, addr);
+        assert_eq!(addr, ip6);
+
+        let other = "fc00::2".parse::<Ipv6Addr>().expect("bad test ipv6");
+        assert_ne!(ip6, other);
+        assert_ne!(other, ip6);
+        assert_ne!(ip6, Ipv6Addr::UNSPECIFIED);
+    }
+
+    #[test]
+    fn test_ip6_parse() {
+        let ip6 = ipv6("fc00:0000:0000:0000:0000:0000:0000:0001");
+        assert_eq!("fc00::1".parse::<CJDNS_IP6>(), Ok(ip6.clone()));
+        assert_eq!("fc00:0:0:0:0:0:0:1".parse::<CJDNS_IP6>(), Ok(ip6.clone()));
+        assert_eq!("FC00::0001".parse::<CJDNS_IP6>(), Ok(ip6.clone()));
+        assert_eq!("fc00:0000:0000:0000:0000:0000:0000:0001".parse::<CJDNS_IP6>(), Ok(ip6.clone()));
+        assert_eq!(Ipv6Addr::from(&ip6).to_string(), "fc00::1");
+
+        let ip6 = ipv6("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58");
+        assert_eq!(ip6.to_string().parse::<CJDNS_IP6>(), Ok(ip6.clone()));
+        assert_eq!(Ipv6Addr::from(&ip6).to_string().parse::<CJDNS_IP6>(), Ok(ip6.clone()));
+        assert_eq!("FC32:6a5d:e235:7057:e990:6398:5D7a:Aa58".parse::<CJDNS_IP6>(), Ok(ip6));
+
+        assert_eq!("fd00::1".parse::<CJDNS_IP6>(), Err(KeyCreationError::ResultingIp6OutOfValidRange));
+        assert_eq!("::".parse::<CJDNS_IP6>(), Err(KeyCreationError::ResultingIp6OutOfValidRange));
+        assert_eq!("fc00::1::2".parse::<CJDNS_IP6>(), Err(KeyCreationError::BadString));
+        assert_eq!("fc32:6a5d7057:e990:6398:5d7a:aa58".parse::<CJDNS_IP6>(), Err(KeyCreationError::BadString));
+        assert_eq!("".parse::<CJDNS_IP6>(), Err(KeyCreationError::BadString));
+    }
+
+    #[test]
+    fn test_ip6_compressed() {
+        let ip6 = ipv6("fc00:0000:0000:0000:0000:0000:0000:0001");
+        assert_eq!(ip6.to_compressed_string(), "fc00::1");
+        assert_eq!(format!("{:#}", ip6), "fc00::1");
+        assert_eq!(ip6.to_string(), "fc00:0000:0000:0000:0000:0000:0000:0001");
+        assert_eq!(ip6.to_compressed_string().parse::<CJDNS_IP6>(), Ok(ip6.clone()));
+        assert_eq!(ip6.to_string().parse::<CJDNS_IP6>(), Ok(ip6));
+
+        let ip6 = ipv6("fc32:6a5d:0000:0000:e990:0000:0000:0000");
+        assert_eq!(ip6.to_compressed_string(), "fc32:6a5d:0:0:e990::");
+        assert_eq!(ip6.to_compressed_string().parse::<CJDNS_IP6>(), Ok(ip6));
+
+        let ip6 = ipv6("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58");
+        assert_eq!(ip6.to_compressed_string(), ip6.to_string());
+        assert_eq!(ip6.to_compressed_string().parse::<CJDNS_IP6>(), Ok(ip6));
+    }
+
     #[test]
     fn test_zero_ip6() {
         let zeroes = [0_u8; 16];
         let zero = CJDNS_IP6::try_from(&zeroes[..]).expect("failed to construct zero IPv6");
         assert!(zero.is_zero());
         assert!(!ipv6("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58").is_zero());
+        assert_eq!(CJDNS_IP6::try_from(&zeroes[..15]), Err(KeyCreationError::InvalidLength { expected: 16, got: 15 }));
+    }
+
+    #[test]
+    fn test_ip6_bytes() {
+        let ip6 = ipv6("fc32:6a5d:e235:7057:e990:6398:5d7a:aa58");
+        let octets = [0xfc, 0x32, 0x6a, 0x5d, 0xe2, 0x35, 0x70, 0x57, 0xe9, 0x90, 0x63, 0x98, 0x5d, 0x7a, 0xaa, 0x58];
+        assert_eq!(ip6.octets(), octets);
+        assert_eq!(&ip6.octets(), ip6.raw());
+        assert_eq!(CJDNS_IP6::try_from(octets), Ok(ip6.clone()));
+        assert_eq!(CJDNS_IP6::try_from(&octets[..]), Ok(ip6.clone()));
+        assert_eq!(CJDNS_IP6::try_from(Ipv6Addr::from(&ip6).octets()), Ok(ip6));
+
+        let mut octets = octets;
+        octets[0] = 0xfd;
+        assert_eq!(CJDNS_IP6::try_from(octets), Err(KeyCreationError::ResultingIp6OutOfValidRange));
+        assert_eq!(CJDNS_IP6::try_from(&octets[..]), Err(KeyCreationError::ResultingIp6OutOfValidRange));
     }
 }
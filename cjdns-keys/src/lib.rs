@@ -3,11 +3,13 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub use address::CJDNSAddress;
 pub use api::{CJDNSKeys, CJDNSKeysApi};
 pub use ip6::CJDNS_IP6;
 pub use priv_key::CJDNSPrivateKey;
 pub use pub_key::CJDNSPublicKey;
 
+mod address;
 mod api;
 mod errors;
 mod ip6;
@@ -7,10 +7,13 @@ use regex::Regex;
 
 use cjdns_crypto::random::Random;
 use cjdns_crypto::scalarmult;
+use cjdns_crypto::utils;
 
 use crate::{
+    CJDNS_IP6,
+    CJDNSPublicKey,
     errors::{KeyCreationError, Result},
-    utils::{debug_fmt, vec_to_array32},
+    utils::{check_str, vec_to_array32},
 };
 
 lazy_static! {
@@ -18,6 +21,8 @@ lazy_static! {
 }
 
 /// CJDNS private key type
+///
+/// Key bytes are wiped out when the key is dropped.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CJDNSPrivateKey {
     k: [u8; 32],
@@ -33,9 +38,10 @@ impl TryFrom<&str> for CJDNSPrivateKey {
 
     fn try_from(value: &str) -> Result<Self> {
         if PRIVATE_KEY_RE.is_match(value) {
-            let bytes = hex::decode(value).expect("invalid hex string");
-            return Ok(CJDNSPrivateKey { k: vec_to_array32(bytes) });
+            let bytes = hex::decode(value).or(Err(KeyCreationError::BadString))?;
+            return Ok(CJDNSPrivateKey { k: vec_to_array32(bytes)? });
         }
+        check_str(value, 2 * Self::SIZE, |c| c.is_ascii_hexdigit())?;
         Err(KeyCreationError::BadString)
     }
 }
@@ -54,9 +60,32 @@ impl Deref for CJDNSPrivateKey {
     }
 }
 
+impl AsRef<[u8]> for CJDNSPrivateKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.k
+    }
+}
+
+impl Drop for CJDNSPrivateKey {
+    fn drop(&mut self) {
+        // Volatile writes can't be optimized out, even though the memory is never read afterwards.
+        for byte in self.k.iter_mut() {
+            unsafe { std::ptr::write_volatile(byte, 0) };
+        }
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl std::fmt::Display for CJDNSPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(&self.k))
+    }
+}
+
+/// Key bytes are never shown, so that keys don't leak into logs. Use `Display` to get the key string.
 impl std::fmt::Debug for CJDNSPrivateKey {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        debug_fmt(self.k, f)
+        f.write_str("CJDNSPrivateKey(***)")
     }
 }
 
@@ -67,10 +96,45 @@ impl CJDNSPrivateKey {
         CJDNSPrivateKey { k: random_bytes }
     }
 
+    /// Derives public key from this private key, same as `CJDNSPublicKey::from`.
+    pub fn to_public_key(&self) -> CJDNSPublicKey {
+        CJDNSPublicKey::from(self)
+    }
+
+    /// Derives node ip6 from this private key, through its public key.
+    ///
+    /// Returns an error if the resulting ip6 doesn't start with `0xFC`, i.e. the key is not a valid CJDNS key.
+    pub fn to_address(&self) -> Result<CJDNS_IP6> {
+        CJDNS_IP6::try_from(&self.to_public_key())
+    }
+
+    /// Returns the key as a scalar for `scalarmult` operations.
+    ///
+    /// The returned `Scalar` is a copy of the secret, it is wiped out by sodiumoxide when dropped,
+    /// so keep it only for as long as it's needed.
     pub fn to_scalar(&self) -> scalarmult::Scalar {
         scalarmult::Scalar(self.k)
     }
 
+    /// Computes X25519 shared secret of this key and the `peer` public key, as used by the CryptoAuth handshake.
+    ///
+    /// The result is the raw curve point, it is not hashed. Returns `KeyCreationError::LowOrderPublicKey` if the result
+    /// is all zeroes, which is the case for some malicious (low order) public keys.
+    pub fn shared_secret(&self, peer: &CJDNSPublicKey) -> Result<[u8; 32]> {
+        let peer_point = scalarmult::GroupElement(*peer.raw());
+        scalarmult::scalarmult(&self.to_scalar(), &peer_point)
+            .map(|secret| secret.0)
+            .or(Err(KeyCreationError::LowOrderPublicKey))
+    }
+
+    /// Compares keys in constant time.
+    ///
+    /// Unlike `==`, which stops on the first differing byte, the time this takes doesn't depend on the keys contents,
+    /// so this is the way to compare a presented key against an authorized or a secret one.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        utils::memcmp(&self.k, &other.k)
+    }
+
     pub fn is_zero(&self) -> bool {
         self.k == [0; 32]
     }
@@ -106,14 +170,95 @@ mod tests {
         // wrong len - too small
     }
 
+    #[test]
+    fn test_private_key_from_string_errors() {
+        assert_eq!(
+            priv_key_r("378813HfIcc62185jfab4d00030b55f50b54e515bfcea8b41f2bd1c2511bae03"),
+            Err(KeyCreationError::BadCharacter { character: 'H', position: 6 })
+        );
+        assert_eq!(
+            priv_key_r("378813dfecc62185ffAb4d00030b55f50b54e515bfceA8b41f2bd1c2511Bae0"),
+            Err(KeyCreationError::BadStringLength { expected: 64, got: 63 })
+        );
+        assert_eq!(
+            priv_key_r("378813dfecc62185ffab4d00030b55f50b54e515bfcea8b41f2bd1c2511bae03\n"),
+            Err(KeyCreationError::BadStringLength { expected: 64, got: 65 })
+        );
+        assert_eq!(priv_key_r(""), Err(KeyCreationError::BadStringLength { expected: 64, got: 0 }));
+    }
+
     #[test]
     fn test_to_from_bytes() {
         let priv_key = priv_key("90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd");
         let priv_key_bytes = priv_key.k;
         assert_eq!(&(*priv_key), &priv_key_bytes);
+        assert_eq!(priv_key.as_ref(), &priv_key_bytes);
+        assert_eq!(hex::encode(&priv_key), "90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd");
         assert_eq!(CJDNSPrivateKey::from(priv_key_bytes), priv_key);
     }
 
+    #[test]
+    fn test_to_from_string() {
+        let priv_key_str = "90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd";
+        assert_eq!(priv_key(priv_key_str).to_string(), priv_key_str);
+        assert_eq!(
+            priv_key("378813dfecc62185ffAb4d00030b55f50b54e515bfceA8b41f2bd1c2511Bae03").to_string(),
+            "378813dfecc62185ffab4d00030b55f50b54e515bfcea8b41f2bd1c2511bae03"
+        );
+        assert_eq!(CJDNSPrivateKey::try_from(priv_key(priv_key_str).to_string().as_str()), Ok(priv_key(priv_key_str)));
+    }
+
+    #[test]
+    fn test_debug_redacted() {
+        let key = priv_key("90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd");
+        assert_eq!(format!("{:?}", key), "CJDNSPrivateKey(***)");
+        assert_eq!(format!("{:#?}", key), "CJDNSPrivateKey(***)");
+        assert_eq!(format!("{:?}", Some(&key)), "Some(CJDNSPrivateKey(***))");
+    }
+
+    #[test]
+    fn test_derivation() {
+        let priv_key = priv_key("90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd");
+        let pub_key = CJDNSPublicKey::from(&priv_key);
+        assert_eq!(priv_key.to_public_key(), pub_key);
+        assert_eq!(priv_key.to_address(), CJDNS_IP6::try_from(&pub_key));
+    }
+
+    #[test]
+    fn test_wipe_on_drop() {
+        let mut key = std::mem::ManuallyDrop::new(priv_key("90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd"));
+        assert!(!key.is_zero());
+        unsafe { std::ptr::drop_in_place(&mut *key) };
+        assert_eq!(key.k, [0; 32]);
+    }
+
+    #[test]
+    fn test_to_scalar() {
+        let key = priv_key("90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd");
+        assert_eq!(&key.to_scalar().0, key.raw());
+    }
+
+    #[test]
+    fn test_shared_secret() {
+        let alice = priv_key("90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd");
+        let bob = priv_key("378813dfecc62185ffab4d00030b55f50b54e515bfcea8b41f2bd1c2511bae03");
+        let alice_secret = alice.shared_secret(&CJDNSPublicKey::from(&bob)).expect("failed to compute shared secret");
+        let bob_secret = bob.shared_secret(&CJDNSPublicKey::from(&alice)).expect("failed to compute shared secret");
+        assert_eq!(alice_secret, bob_secret);
+        assert_ne!(alice_secret, [0; 32]);
+
+        // zero is a low order point
+        assert_eq!(alice.shared_secret(&CJDNSPublicKey::from([0; 32])), Err(KeyCreationError::LowOrderPublicKey));
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let key = priv_key("90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd");
+        assert!(key.ct_eq(&priv_key("90a66780a0dc2ca735bc0c161d3e92c876935981e8658c32a846f79947a923bd")));
+        assert!(!key.ct_eq(&priv_key("378813dfecc62185ffab4d00030b55f50b54e515bfcea8b41f2bd1c2511bae03")));
+        assert!(!key.ct_eq(&CJDNSPrivateKey::from([0; 32])));
+    }
+
     #[test]
     fn test_zero_priv_key() {
         let zeroes = [0_u8; 32];
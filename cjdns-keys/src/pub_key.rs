@@ -2,25 +2,35 @@
 
 use std::convert::TryFrom;
 use std::ops::Deref;
+use std::str::FromStr;
 
 use data_encoding::BASE32_DNSCURVE;
 use regex::Regex;
 
+use cjdns_crypto::hash::sha512;
+#[cfg(any(test, feature = "test-utils"))]
+use cjdns_crypto::random::Random;
 use cjdns_crypto::scalarmult;
+use cjdns_crypto::utils;
 
 use crate::{
+    CJDNS_IP6,
     CJDNSPrivateKey,
     errors::{KeyCreationError, Result},
-    utils::{debug_fmt, vec_to_array32},
+    utils::{check_str, debug_fmt, slice_to_array32, vec_to_array32},
 };
 
 lazy_static! {
-    static ref PUBLIC_KEY_RE: Regex = Regex::new(r"[a-z0-9]{52}\.k").expect("bad regexp");
+    static ref PUBLIC_KEY_RE: Regex = Regex::new(r"^[a-z0-9]{52}\.k$").expect("bad regexp");
 }
 
 // Pub key len is 54, where last two characters are `.k`. So first 52 are the encoded ones.
 const BASE32_ENCODED_STRING_LEN: usize = 52;
 
+fn check_base32(s: &str) -> Result<()> {
+    check_str(s, BASE32_ENCODED_STRING_LEN, |c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
 /// CJDNS public key type
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CJDNSPublicKey {
@@ -37,15 +47,32 @@ impl TryFrom<&str> for CJDNSPublicKey {
 
     fn try_from(value: &str) -> Result<Self> {
         if PUBLIC_KEY_RE.is_match(value) {
-            let bytes = BASE32_DNSCURVE
-                .decode(value[..BASE32_ENCODED_STRING_LEN].as_bytes())
-                .or(Err(KeyCreationError::NotDecodableString))?;
-            return Ok(CJDNSPublicKey { k: vec_to_array32(bytes) });
+            return Self::decode_base32(&value[..BASE32_ENCODED_STRING_LEN]);
+        }
+        if !value.ends_with(".k") {
+            return Err(KeyCreationError::BadString);
         }
+        check_base32(&value[..value.len() - 2])?;
         Err(KeyCreationError::BadString)
     }
 }
 
+impl TryFrom<&[u8]> for CJDNSPublicKey {
+    type Error = KeyCreationError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        Ok(CJDNSPublicKey { k: slice_to_array32(bytes)? })
+    }
+}
+
+impl FromStr for CJDNSPublicKey {
+    type Err = KeyCreationError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::try_from(s)
+    }
+}
+
 impl From<&CJDNSPrivateKey> for CJDNSPublicKey {
     fn from(value: &CJDNSPrivateKey) -> Self {
         let pub_key_bytes = scalarmult::scalarmult_base(&value.to_scalar()).0;
@@ -59,6 +86,27 @@ impl From<[u8; 32]> for CJDNSPublicKey {
     }
 }
 
+#[cfg(feature = "scalarmult")]
+impl From<scalarmult::GroupElement> for CJDNSPublicKey {
+    fn from(group_element: scalarmult::GroupElement) -> Self {
+        CJDNSPublicKey { k: group_element.0 }
+    }
+}
+
+/// Compares the key with a key string, which is parsed with `CJDNSPublicKey::from_str_lenient`,
+/// so surrounding whitespace and case don't matter. Unparsable strings are not equal to any key.
+impl PartialEq<str> for CJDNSPublicKey {
+    fn eq(&self, other: &str) -> bool {
+        CJDNSPublicKey::from_str_lenient(other).map_or(false, |other| *self == other)
+    }
+}
+
+impl PartialEq<&str> for CJDNSPublicKey {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
 impl Deref for CJDNSPublicKey {
     type Target = [u8];
 
@@ -67,19 +115,90 @@ impl Deref for CJDNSPublicKey {
     }
 }
 
+impl AsRef<[u8]> for CJDNSPublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.k
+    }
+}
+
 impl std::fmt::Display for CJDNSPublicKey {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", BASE32_DNSCURVE.encode(&self.k) + ".k")
+        write!(f, "{}.k", self.to_base32())
     }
 }
 
+/// Formats the key in its canonical `<base32>.k` form, the alternate form (`{:#?}`) adds key bytes in hex.
 impl std::fmt::Debug for CJDNSPublicKey {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        debug_fmt(self.k, f)
+        write!(f, "{}", self)?;
+        if f.alternate() {
+            f.write_str(" (")?;
+            debug_fmt(self.k, f)?;
+            f.write_str(")")?;
+        }
+        Ok(())
     }
 }
 
 impl CJDNSPublicKey {
+    /// Creates a key of random bytes, e.g. as a test fixture for code which indexes by key.
+    /// Only available with `test-utils` feature.
+    ///
+    /// The result is not derived from any private key and is not even guaranteed to be a valid curve point,
+    /// nor to give a valid `fc` ip6, so it must never be used for cryptography. Use `CJDNSKeys` to generate real keys.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn random<R: Random>(rand: &R) -> Self {
+        let mut random_bytes = [0_u8; Self::SIZE];
+        rand.random_bytes(&mut random_bytes);
+        CJDNSPublicKey { k: random_bytes }
+    }
+
+    /// Parses a key pasted by a human, e.g. from logs: surrounding whitespace is ignored and the key may be in any case.
+    ///
+    /// `TryFrom<&str>` and `FromStr` only accept keys in the canonical lowercase form, as they are used on the wire.
+    pub fn from_str_lenient(s: &str) -> Result<Self> {
+        Self::try_from(s.trim().to_ascii_lowercase().as_str())
+    }
+
+    /// Full hash the node ip6 is derived from: `sha512(sha512(key))`, the SHA-512 of the SHA-512 digest of the 32 key bytes.
+    ///
+    /// Address is the first 16 bytes of this hash, see `to_address`. The rest is not used by cjdns, it is exposed to verify
+    /// the derivation step by step, e.g. against the reference implementation.
+    pub fn address_hash(&self) -> [u8; 64] {
+        sha512::hash(&sha512::hash(&self.k).0).0
+    }
+
+    /// Derives node ip6 from this public key, same as `CJDNS_IP6::try_from`.
+    ///
+    /// Returns an error if the resulting ip6 doesn't start with `0xFC`, i.e. the key is not a valid CJDNS key.
+    pub fn to_address(&self) -> Result<CJDNS_IP6> {
+        CJDNS_IP6::try_from(self)
+    }
+
+    /// Encodes the key as a bare 52 characters base32 string, without the `.k` suffix used by `Display`.
+    pub fn to_base32(&self) -> String {
+        BASE32_DNSCURVE.encode(&self.k)
+    }
+
+    /// Parses a bare 52 characters base32 string without the `.k` suffix, as returned by `to_base32`.
+    pub fn from_base32(s: &str) -> Result<Self> {
+        check_base32(s)?;
+        Self::decode_base32(s)
+    }
+
+    fn decode_base32(s: &str) -> Result<Self> {
+        let bytes = BASE32_DNSCURVE.decode(s.as_bytes()).or(Err(KeyCreationError::NotDecodableString))?;
+        Ok(CJDNSPublicKey { k: vec_to_array32(bytes)? })
+    }
+
+    /// Compares keys in constant time.
+    ///
+    /// Unlike `==`, which stops on the first differing byte, the time this takes doesn't depend on the keys contents,
+    /// so this is the way to compare a presented key against an authorized or a secret one.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        utils::memcmp(&self.k, &other.k)
+    }
+
     pub fn is_zero(&self) -> bool {
         self.k == [0; 32]
     }
@@ -87,6 +206,12 @@ impl CJDNSPublicKey {
     pub fn raw(&self) -> &[u8; Self::SIZE] {
         &self.k
     }
+
+    /// Returns the key as a curve point for `scalarmult` operations, the opposite of `From<scalarmult::GroupElement>`.
+    #[cfg(feature = "scalarmult")]
+    pub fn to_group_element(&self) -> scalarmult::GroupElement {
+        scalarmult::GroupElement(self.k)
+    }
 }
 
 #[cfg(test)]
@@ -114,6 +239,13 @@ mod tests {
             // wrong alphabet
             pub_key_r("XPR2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"),
             pub_key_r("aer2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"),
+            // leading or trailing garbage
+            pub_key_r("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k.extra"),
+            pub_key_r("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.kk"),
+            pub_key_r("garbage.xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"),
+            pub_key_r("0xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"),
+            pub_key_r(" xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"),
+            pub_key_r("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k\n"),
             // can not be decoded
             pub_key_r("xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx.k"),
             // can not be decoded, takes lots of bytes - last char = 8
@@ -122,6 +254,117 @@ mod tests {
         for err_res in invalid_pub_keys {
             assert!(err_res.is_err())
         }
+        assert_eq!(pub_key_r("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k.extra"), Err(KeyCreationError::BadString));
+        assert_eq!(pub_key_r("0xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"), Err(KeyCreationError::BadStringLength { expected: 52, got: 53 }));
+        assert_eq!(pub_key_r("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.kk"), Err(KeyCreationError::BadString));
+        assert_eq!(pub_key_r(".k"), Err(KeyCreationError::BadStringLength { expected: 52, got: 0 }));
+        assert_eq!(pub_key_r("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yY0.k"), Err(KeyCreationError::BadCharacter { character: 'Y', position: 50 }));
+        assert_eq!(pub_key_r("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840y-0.k"), Err(KeyCreationError::BadCharacter { character: '-', position: 50 }));
+
+        // non-zero trailing bits of the last char are rejected by base32 decoding
+        assert_eq!(pub_key_r("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy8.k"), Err(KeyCreationError::NotDecodableString));
+        assert_eq!(pub_key_r("xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx.k"), Err(KeyCreationError::NotDecodableString));
+    }
+
+    #[test]
+    fn test_public_key_parse() {
+        let key_str = "xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k";
+        assert_eq!(key_str.parse::<CJDNSPublicKey>(), Ok(pub_key(key_str)));
+        assert_eq!(key_str.parse::<CJDNSPublicKey>().map(|k| k.to_string()), Ok(key_str.to_string()));
+        assert_eq!("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0".parse::<CJDNSPublicKey>(), Err(KeyCreationError::BadString));
+    }
+
+    #[test]
+    fn test_public_key_lenient() {
+        let key = pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        assert_eq!(CJDNSPublicKey::from_str_lenient("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"), Ok(key.clone()));
+        assert_eq!(CJDNSPublicKey::from_str_lenient("XPR2Z2S3HNR0QZPK2U121UQJV15DC335V54PCCQLQJ6C5P840YY0.K"), Ok(key.clone()));
+        assert_eq!(CJDNSPublicKey::from_str_lenient(" Xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k\n"), Ok(key.clone()));
+        assert_eq!(CJDNSPublicKey::from_str_lenient("XPR2Z2S3HNR0QZPK2U121UQJV15DC335V54PCCQLQJ6C5P840YY0.K").map(|k| k.to_string()), Ok(key.to_string()));
+
+        // the strict parsing is still case-sensitive
+        assert_eq!(pub_key_r("XPR2Z2S3HNR0QZPK2U121UQJV15DC335V54PCCQLQJ6C5P840YY0.K"), Err(KeyCreationError::BadString));
+        assert_eq!(CJDNSPublicKey::from_str_lenient("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0"), Err(KeyCreationError::BadString));
+        assert_eq!(
+            CJDNSPublicKey::from_str_lenient("xpr2z2s3 hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"),
+            Err(KeyCreationError::BadStringLength { expected: 52, got: 53 })
+        );
+    }
+
+    #[test]
+    fn test_random() {
+        let rand = cjdns_crypto::random::DefaultRandom::new().expect("failed to init random");
+        let key = CJDNSPublicKey::random(&rand);
+        assert!(!key.is_zero());
+        assert_ne!(key, CJDNSPublicKey::random(&rand));
+        assert_eq!(CJDNSPublicKey::try_from(key.to_string().as_str()), Ok(key));
+    }
+
+    #[test]
+    #[cfg(feature = "scalarmult")]
+    fn test_group_element() {
+        let key = pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        let group_element = key.to_group_element();
+        assert_eq!(&group_element.0, key.raw());
+        assert_eq!(CJDNSPublicKey::from(group_element), key);
+        assert_eq!(CJDNSPublicKey::from(scalarmult::GroupElement([7; 32])), CJDNSPublicKey::from([7; 32]));
+    }
+
+    #[test]
+    fn test_address_hash() {
+        let key = pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        let hash = key.address_hash();
+        assert_eq!(hash, sha512::hash(&sha512::hash(key.raw()).0).0);
+        assert_eq!(hex::encode(&hash[..16]), "fc326a5de2357057e99063985d7aaa58");
+        assert_eq!(key.to_address().map(|ip6| *ip6.raw()), Ok(<[u8; 16]>::try_from(&hash[..16]).expect("bad hash length")));
+        assert_ne!(hash, pub_key("qgkjd0stfvk9r3j28s4gh8rgslbgx2r5xgxzxkgm5vdxqwn8xsu0.k").address_hash());
+
+        let zero = CJDNSPublicKey::from([0; 32]);
+        assert_ne!(zero.address_hash()[0], 0xfc);
+        assert_eq!(zero.to_address(), Err(KeyCreationError::ResultingIp6OutOfValidRange));
+    }
+
+    #[test]
+    fn test_debug() {
+        let key = pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        assert_eq!(format!("{:?}", key), "xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        assert_eq!(format!("{:#?}", key), format!("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k ({})", hex::encode(&key)));
+    }
+
+    #[test]
+    fn test_eq_str() {
+        let key = pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        assert!(key == "xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        assert!(key == *"xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        assert!(key == " XPR2Z2S3HNR0QZPK2U121UQJV15DC335V54PCCQLQJ6C5P840YY0.K ");
+        assert_eq!(key, key.to_string().as_str());
+
+        assert!(key != "qgkjd0stfvk9r3j28s4gh8rgslbgx2r5xgxzxkgm5vdxqwn8xsu0.k");
+        assert!(key != "xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0");
+        assert_ne!(key, "");
+    }
+
+    #[test]
+    fn test_base32() {
+        let key = pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        assert_eq!(key.to_base32(), "xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0");
+        assert_eq!(CJDNSPublicKey::from_base32("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0"), Ok(key.clone()));
+        assert_eq!(CJDNSPublicKey::from_base32(&key.to_base32()), Ok(key));
+
+        assert_eq!(
+            CJDNSPublicKey::from_base32("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k"),
+            Err(KeyCreationError::BadStringLength { expected: 52, got: 54 })
+        );
+        assert_eq!(
+            CJDNSPublicKey::from_base32("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy"),
+            Err(KeyCreationError::BadStringLength { expected: 52, got: 51 })
+        );
+        assert_eq!(
+            CJDNSPublicKey::from_base32("XPR2Z2S3HNR0QZPK2U121UQJV15DC335V54PCCQLQJ6C5P840YY0"),
+            Err(KeyCreationError::BadCharacter { character: 'X', position: 0 })
+        );
+        assert_eq!(CJDNSPublicKey::from_base32("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy8"), Err(KeyCreationError::NotDecodableString));
+        assert_eq!(CJDNSPublicKey::from_base32(""), Err(KeyCreationError::BadStringLength { expected: 52, got: 0 }));
     }
 
     #[test]
@@ -129,10 +372,24 @@ mod tests {
         let pub_key = pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
         let pub_key_bytes = pub_key.k;
         assert_eq!(&*pub_key, &pub_key_bytes);
+        assert_eq!(pub_key.as_ref(), &pub_key_bytes);
+        assert_eq!(hex::encode(&pub_key), hex::encode(pub_key_bytes));
         assert_eq!(CJDNSPublicKey::from(pub_key_bytes), pub_key);
+        assert_eq!(CJDNSPublicKey::try_from(&pub_key_bytes[..]), Ok(pub_key.clone()));
+        assert_eq!(CJDNSPublicKey::try_from(&pub_key_bytes[..31]), Err(KeyCreationError::InvalidLength { expected: 32, got: 31 }));
+        assert_eq!(CJDNSPublicKey::try_from(&[0_u8; 33][..]), Err(KeyCreationError::InvalidLength { expected: 32, got: 33 }));
+        assert_eq!(CJDNSPublicKey::try_from(&[][..]), Err(KeyCreationError::InvalidLength { expected: 32, got: 0 }));
         assert_eq!(pub_key.to_string(), "xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k".to_string());
     }
 
+    #[test]
+    fn test_ct_eq() {
+        let key = pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k");
+        assert!(key.ct_eq(&pub_key("xpr2z2s3hnr0qzpk2u121uqjv15dc335v54pccqlqj6c5p840yy0.k")));
+        assert!(!key.ct_eq(&pub_key("qgkjd0stfvk9r3j28s4gh8rgslbgx2r5xgxzxkgm5vdxqwn8xsu0.k")));
+        assert!(!key.ct_eq(&CJDNSPublicKey::from([0; 32])));
+    }
+
     #[test]
     fn test_zero_pub_key() {
         let zeroes = [0_u8; 32];
@@ -1,22 +1,80 @@
 //! Utility functions
 
-pub(crate) fn vec_to_array32(vec: Vec<u8>) -> [u8; 32] {
+use crate::errors::{KeyCreationError, Result};
+
+pub(crate) fn vec_to_array32(vec: Vec<u8>) -> Result<[u8; 32]> {
+    slice_to_array32(&vec)
+}
+
+pub(crate) fn slice_to_array32(slice: &[u8]) -> Result<[u8; 32]> {
     let mut array = [0u8; 32];
-    array.copy_from_slice(&vec);
-    array
+    check_len(slice, array.len())?;
+    array.copy_from_slice(slice);
+    Ok(array)
 }
 
-pub(crate) fn vec_to_array16(vec: Vec<u8>) -> [u8; 16] {
+pub(crate) fn vec_to_array16(vec: Vec<u8>) -> Result<[u8; 16]> {
     slice_to_array16(&vec)
 }
 
-pub(crate) fn slice_to_array16(slice: &[u8]) -> [u8; 16] {
+pub(crate) fn slice_to_array16(slice: &[u8]) -> Result<[u8; 16]> {
     let mut array = [0u8; 16];
+    check_len(slice, array.len())?;
     array.copy_from_slice(slice);
-    array
+    Ok(array)
+}
+
+fn check_len(slice: &[u8], expected: usize) -> Result<()> {
+    if slice.len() != expected {
+        return Err(KeyCreationError::InvalidLength { expected, got: slice.len() });
+    }
+    Ok(())
+}
+
+/// Checks the string length and its characters, to tell why a string which didn't match the expected format was rejected.
+///
+/// Length is checked first, and `position` of a bad character is a byte offset.
+pub(crate) fn check_str(s: &str, expected: usize, is_valid_char: impl Fn(char) -> bool) -> Result<()> {
+    if s.len() != expected {
+        return Err(KeyCreationError::BadStringLength { expected, got: s.len() });
+    }
+    match s.char_indices().find(|&(_, c)| !is_valid_char(c)) {
+        Some((position, character)) => Err(KeyCreationError::BadCharacter { character, position }),
+        None => Ok(()),
+    }
 }
 
 pub(crate) fn debug_fmt<T: AsRef<[u8]>>(bytes: T, f: &mut std::fmt::Formatter) -> std::fmt::Result {
     let s = hex::encode(bytes);
     f.write_str(&s)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_array() {
+        assert_eq!(slice_to_array32(&[7; 32]), Ok([7; 32]));
+        assert_eq!(vec_to_array32(vec![7; 32]), Ok([7; 32]));
+        assert_eq!(slice_to_array16(&[7; 16]), Ok([7; 16]));
+        assert_eq!(vec_to_array16(vec![7; 16]), Ok([7; 16]));
+
+        assert_eq!(slice_to_array32(&[7; 33]), Err(KeyCreationError::InvalidLength { expected: 32, got: 33 }));
+        assert_eq!(vec_to_array32(vec![]), Err(KeyCreationError::InvalidLength { expected: 32, got: 0 }));
+        assert_eq!(slice_to_array16(&[7; 32]), Err(KeyCreationError::InvalidLength { expected: 16, got: 32 }));
+        assert_eq!(vec_to_array16(vec![7; 15]), Err(KeyCreationError::InvalidLength { expected: 16, got: 15 }));
+    }
+
+    #[test]
+    fn test_check_str() {
+        let is_digit = |c: char| c.is_ascii_digit();
+        assert_eq!(check_str("0123", 4, is_digit), Ok(()));
+        assert_eq!(check_str("", 0, is_digit), Ok(()));
+        assert_eq!(check_str("012", 4, is_digit), Err(KeyCreationError::BadStringLength { expected: 4, got: 3 }));
+        assert_eq!(check_str("01x3", 4, is_digit), Err(KeyCreationError::BadCharacter { character: 'x', position: 2 }));
+        assert_eq!(check_str("x1y3", 4, is_digit), Err(KeyCreationError::BadCharacter { character: 'x', position: 0 }));
+        assert_eq!(check_str("0é", 3, is_digit), Err(KeyCreationError::BadCharacter { character: 'é', position: 1 }));
+        assert_eq!(check_str("0123", 3, |_| false), Err(KeyCreationError::BadStringLength { expected: 3, got: 4 }));
+    }
+}